@@ -14,6 +14,48 @@ pub struct Hot<T: Load> {
 
 pub type Ref<'a, T> = std::cell::Ref<'a, T>;
 
+/// Set the flag when the file (or anything inside the directory) is changed.
+///
+/// Files are watched through their directory so that they are still tracked after being replaced.
+/// Changes stop being tracked when the watcher is dropped
+#[cfg(not(target_arch = "wasm32"))]
+pub fn watch(
+    path: &Path,
+    changed: Arc<std::sync::atomic::AtomicBool>,
+) -> notify::Result<notify::RecommendedWatcher> {
+    use notify::Watcher;
+    let (watched, mode, file_name) = if path.is_dir() {
+        (path, notify::RecursiveMode::Recursive, None)
+    } else {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let file_name = path.file_name().map(|name| name.to_owned());
+        (dir, notify::RecursiveMode::NonRecursive, file_name)
+    };
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if !event.kind.is_modify() && !event.kind.is_create() {
+            return;
+        }
+        if let Some(file_name) = &file_name {
+            if !event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == Some(file_name.as_os_str()))
+            {
+                return;
+            }
+        }
+        changed.store(true, std::sync::atomic::Ordering::SeqCst);
+    })?;
+    watcher.watch(watched, mode)?;
+    Ok(watcher)
+}
+
 impl<T: Load> Hot<T> {
     pub fn get(&self) -> Ref<T> {
         if let Ok(mut current) = self.current.try_borrow_mut() {
@@ -51,19 +93,7 @@ impl<T: Load> Load for Hot<T> {
         let need_update = Arc::new(std::sync::atomic::AtomicBool::new(false));
         #[cfg(not(target_arch = "wasm32"))]
         let watcher = if manager.hot_reload_enabled() {
-            use notify::Watcher;
-            let need_update = need_update.clone();
-            let mut watcher =
-                notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
-                    let event = result.unwrap();
-                    if event.kind.is_modify() {
-                        need_update.store(true, std::sync::atomic::Ordering::SeqCst);
-                    }
-                })
-                .unwrap();
-            watcher
-                .watch(&path, notify::RecursiveMode::Recursive)
-                .unwrap();
+            let watcher = watch(&path, need_update.clone()).unwrap();
             log::info!("watching {path:?}");
             Some(watcher)
         } else {
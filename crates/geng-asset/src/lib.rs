@@ -27,6 +27,7 @@ struct ManagerImpl {
     shader_lib: shader::Library,
     hot_reload_enabled: bool,
     marked_paths: RefCell<Vec<PathBuf>>,
+    load_progress: RefCell<LoadProgress>,
 }
 
 #[derive(Clone)]
@@ -49,6 +50,7 @@ impl Manager {
                 shader_lib: shader::Library::new(window.ugli(), true, None),
                 hot_reload_enabled: hot_reload,
                 marked_paths: Default::default(),
+                load_progress: Default::default(),
             }),
         }
     }
@@ -77,21 +79,48 @@ impl Manager {
             .borrow_mut()
             .push(path.as_ref().to_owned());
     }
+    /// Number of loads requested and finished so far (nested loads are counted separately)
+    pub fn load_progress(&self) -> LoadProgress {
+        self.inner.load_progress.borrow().clone()
+    }
+    /// Count the load when the future is first polled and when it finishes
+    fn track<T: 'static>(
+        &self,
+        future: Future<T>,
+        size: impl Fn(&T) -> usize + 'static,
+    ) -> Future<T> {
+        let inner = self.inner.clone();
+        async move {
+            inner.load_progress.borrow_mut().total += 1;
+            let result = future.await;
+            let mut progress = inner.load_progress.borrow_mut();
+            progress.progress += 1;
+            if let Ok(value) = &result {
+                progress.bytes += size(value);
+            }
+            result
+        }
+        .boxed_local()
+    }
+    /// Load asset from given path.
+    ///
+    /// Path is not restricted to the assets directory,
+    /// so files dropped onto the window can be loaded using the path from the event
     pub fn load<T: Load>(&self, path: impl AsRef<Path>) -> Future<T> {
-        T::load(self, path.as_ref(), &Default::default())
+        self.load_with(path, &Default::default())
     }
     pub fn load_with<T: Load>(&self, path: impl AsRef<Path>, options: &T::Options) -> Future<T> {
-        T::load(self, path.as_ref(), options)
+        self.track(T::load(self, path.as_ref(), options), |_| 0)
     }
     pub fn load_string(&self, path: impl AsRef<Path>) -> Future<String> {
         self.mark_path(path.as_ref());
         let path = path.as_ref().to_owned();
-        file::load_string(path).boxed_local()
+        self.track(file::load_string(path).boxed_local(), String::len)
     }
     pub fn load_bytes(&self, path: impl AsRef<Path>) -> Future<Vec<u8>> {
         self.mark_path(path.as_ref());
         let path = path.as_ref().to_owned();
-        file::load_bytes(path).boxed_local()
+        self.track(file::load_bytes(path).boxed_local(), Vec::len)
     }
     pub fn load_serde<T: 'static + serde::de::DeserializeOwned>(
         &self,
@@ -99,7 +128,7 @@ impl Manager {
     ) -> Future<T> {
         self.mark_path(path.as_ref());
         let path = path.as_ref().to_owned();
-        file::load_detect(path).boxed_local()
+        self.track(file::load_detect(path).boxed_local(), |_| 0)
     }
     /// Load asset from given path with specified or default extension
     pub fn load_ext<T: Load>(
@@ -154,6 +183,7 @@ impl Load for ugli::Program {
         let manager = manager.clone();
         async move {
             let glsl: String = glsl.await?;
+            puffin::profile_scope!("compile shader");
             manager.shader_lib().compile(&glsl)
         }
         .boxed_local()
@@ -201,7 +231,8 @@ impl Load for geng_font::Font {
         let path = path.to_owned();
         let options = options.clone();
         async move {
-            let data: Vec<u8> = manager.load_bytes(path).await?;
+            let data: Vec<u8> = manager.load_bytes(&path).await?;
+            puffin::profile_scope!("create font", path.to_string_lossy());
             geng_font::Font::new(manager.ugli(), &data, &options)
         }
         .boxed_local()
@@ -209,10 +240,14 @@ impl Load for geng_font::Font {
     const DEFAULT_EXT: Option<&'static str> = Some("ttf");
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoadProgress {
+    /// Number of finished loads
     pub progress: usize,
+    /// Number of started loads
     pub total: usize,
+    /// Number of bytes loaded, only counting loads where size is known
+    pub bytes: usize,
 }
 
 impl LoadProgress {
@@ -220,6 +255,7 @@ impl LoadProgress {
         Self {
             progress: 0,
             total: 0,
+            bytes: 0,
         }
     }
 }
@@ -300,7 +336,10 @@ impl Load for image::RgbaImage {
     fn load(manager: &Manager, path: &Path, _options: &Self::Options) -> Future<Self> {
         manager
             .load::<Vec<u8>>(path)
-            .map(|data| Ok(image::load_from_memory(&data?)?.to_rgba8()))
+            .map(|data| {
+                puffin::profile_scope!("decode image");
+                Ok(image::load_from_memory(&data?)?.to_rgba8())
+            })
             .boxed_local()
     }
     const DEFAULT_EXT: Option<&'static str> = Some("png");
@@ -10,14 +10,22 @@ pub fn load_texture(
     let options = options.clone();
     async move {
         log::debug!("Loading {:?}", path);
-        let image = image::load_from_memory(&manager.load_bytes(path).await?)?;
+        let data = manager.load_bytes(&path).await?;
+        let image = {
+            puffin::profile_scope!("decode image", path.to_string_lossy());
+            image::load_from_memory(&data)?
+        };
         manager.yield_now().await;
         let mut image = match image {
             image::DynamicImage::ImageRgba8(image) => image,
-            _ => image.to_rgba8(),
+            _ => {
+                puffin::profile_scope!("convert image to rgba");
+                image.to_rgba8()
+            }
         };
         manager.yield_now().await;
         if options.premultiply_alpha {
+            puffin::profile_scope!("premultiply alpha");
             for pixel in image.pixels_mut() {
                 use image::Pixel;
                 *pixel = pixel.map_without_alpha(|x| {
@@ -26,6 +34,7 @@ pub fn load_texture(
             }
         }
         manager.yield_now().await;
+        puffin::profile_scope!("upload texture");
         Ok(ugli::Texture::from_image_image(manager.ugli(), image))
     }
     .boxed_local()
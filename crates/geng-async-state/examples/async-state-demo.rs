@@ -309,7 +309,9 @@ impl SpaceEscape {
         log::info!("Entering depth {:?}", self.depth);
         while let Some(event) = self.window.events().next().await {
             match event {
-                window::Event::KeyPress { key } => match key {
+                window::Event::KeyPress {
+                    key, repeat: false, ..
+                } => match key {
                     window::Key::Escape => {
                         break;
                     }
@@ -1,4 +1,11 @@
+use batbox_color::*;
+use batbox_la::*;
+use batbox_time::Duration;
 use futures::prelude::*;
+use geng_draw2d::{self as draw2d, Draw2d as _};
+use std::rc::Rc;
+
+pub mod transitions;
 
 scoped_tls::scoped_thread_local! { static mut WITH_FRAMEBUFFER: for<'a> &'a mut (dyn 'a + FnMut(&mut dyn FnMut(&mut ugli::Framebuffer))) }
 
@@ -36,6 +43,10 @@ impl<T: ActiveState + ?Sized> ActiveState for Box<T> {
 
 pub trait Transition {
     fn finished(&self) -> bool;
+    /// Advance the transition, called once per frame before drawing
+    fn update(&mut self, delta_time: f64) {
+        #![allow(unused_variables)]
+    }
     fn draw(
         &mut self,
         from: &mut dyn FnMut(&mut ugli::Framebuffer),
@@ -49,6 +60,10 @@ impl Transition for Box<dyn Transition> {
         (**self).finished()
     }
 
+    fn update(&mut self, delta_time: f64) {
+        (**self).update(delta_time)
+    }
+
     fn draw(
         &mut self,
         from: &mut dyn FnMut(&mut ugli::Framebuffer),
@@ -71,7 +86,13 @@ pub async fn transition<T>(
     into: impl Future<Output = T>,
 ) -> T {
     let mut into = std::pin::pin!(into);
+    let mut frames = std::pin::pin!(window
+        .events()
+        .filter(|event| future::ready(matches!(event, geng_window::Event::Draw))));
     let transition_future = std::future::poll_fn(|cx| {
+        while let std::task::Poll::Ready(Some(_)) = frames.as_mut().poll_next(cx) {
+            transition.update(window.delta_time());
+        }
         if transition.finished() {
             std::task::Poll::Ready(None)
         } else {
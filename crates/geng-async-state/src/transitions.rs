@@ -0,0 +1,221 @@
+//! Built-in [Transition] implementations, also used by `geng::state::Manager`
+
+use super::*;
+
+/// Side of the screen the new state comes from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    fn vec(self) -> vec2<f32> {
+        match self {
+            Self::Left => vec2(-1.0, 0.0),
+            Self::Right => vec2(1.0, 0.0),
+            Self::Up => vec2(0.0, 1.0),
+            Self::Down => vec2(0.0, -1.0),
+        }
+    }
+}
+
+/// How the states are drawn during an [Animation]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Effect {
+    /// Fade old state out into the color, then fade new state in
+    Fade(Rgba<f32>),
+    /// Fade new state in over the old one
+    Crossfade,
+    /// New state pushes the old one out of the screen
+    Slide(Direction),
+    /// New state is revealed over the old one
+    Wipe(Direction),
+    /// New state is revealed in a growing circle from the center of the screen
+    CircleWipe,
+}
+
+/// [Transition] drawing both states with an [Effect] during given duration
+pub struct Animation {
+    draw2d: Rc<draw2d::Helper>,
+    effect: Effect,
+    duration: Duration,
+    /// Time passed since the start, in seconds
+    elapsed: f64,
+    from_texture: Option<ugli::Texture>,
+    to_texture: Option<ugli::Texture>,
+}
+
+impl Animation {
+    pub fn new(draw2d: &Rc<draw2d::Helper>, effect: Effect, duration: Duration) -> Self {
+        Self {
+            draw2d: draw2d.clone(),
+            effect,
+            duration,
+            elapsed: 0.0,
+            from_texture: None,
+            to_texture: None,
+        }
+    }
+
+    pub fn fade(draw2d: &Rc<draw2d::Helper>, color: Rgba<f32>, duration: Duration) -> Self {
+        Self::new(draw2d, Effect::Fade(color), duration)
+    }
+
+    pub fn crossfade(draw2d: &Rc<draw2d::Helper>, duration: Duration) -> Self {
+        Self::new(draw2d, Effect::Crossfade, duration)
+    }
+
+    pub fn slide(draw2d: &Rc<draw2d::Helper>, from: Direction, duration: Duration) -> Self {
+        Self::new(draw2d, Effect::Slide(from), duration)
+    }
+
+    pub fn wipe(draw2d: &Rc<draw2d::Helper>, from: Direction, duration: Duration) -> Self {
+        Self::new(draw2d, Effect::Wipe(from), duration)
+    }
+
+    pub fn circle_wipe(draw2d: &Rc<draw2d::Helper>, duration: Duration) -> Self {
+        Self::new(draw2d, Effect::CircleWipe, duration)
+    }
+
+    /// Progress of the animation in range `[0, 1]`
+    pub fn progress(&self) -> f32 {
+        (self.elapsed / self.duration.as_secs_f64()).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Render into a texture of the framebuffer size, reusing it while the size is same
+fn render_texture<'a>(
+    ugli: &ugli::Ugli,
+    texture: &'a mut Option<ugli::Texture>,
+    size: vec2<usize>,
+    f: &mut dyn FnMut(&mut ugli::Framebuffer),
+) -> &'a ugli::Texture {
+    if texture.as_ref().map(|texture| texture.size()) != Some(size) {
+        *texture = Some(ugli::Texture::new_uninitialized(ugli, size));
+    }
+    let texture = texture.as_mut().unwrap();
+    texture.render_into(|framebuffer| {
+        ugli::clear(framebuffer, Some(Rgba::TRANSPARENT_BLACK), None, None);
+        f(framebuffer);
+    });
+    texture
+}
+
+impl Transition for Animation {
+    fn finished(&self) -> bool {
+        self.elapsed >= self.duration.as_secs_f64()
+    }
+
+    fn update(&mut self, delta_time: f64) {
+        self.elapsed += delta_time;
+    }
+
+    fn draw(
+        &mut self,
+        from: &mut dyn FnMut(&mut ugli::Framebuffer),
+        to: &mut dyn FnMut(&mut ugli::Framebuffer),
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let progress = self.progress();
+        let size = framebuffer.size();
+        let screen = Aabb2::ZERO.extend_positive(size.map(|x| x as f32));
+        let camera = &geng_camera::PixelPerfectCamera;
+        let ugli = self.draw2d.ugli().clone();
+        match self.effect {
+            Effect::Fade(color) => {
+                let alpha = if progress < 0.5 {
+                    from(framebuffer);
+                    progress * 2.0
+                } else {
+                    to(framebuffer);
+                    (1.0 - progress) * 2.0
+                };
+                self.draw2d
+                    .quad(framebuffer, camera, screen, Rgba { a: alpha, ..color });
+            }
+            Effect::Crossfade => {
+                from(framebuffer);
+                let texture = render_texture(&ugli, &mut self.to_texture, size, to);
+                self.draw2d.textured_quad(
+                    framebuffer,
+                    camera,
+                    screen,
+                    texture,
+                    Rgba::new(1.0, 1.0, 1.0, progress),
+                );
+            }
+            Effect::Slide(direction) => {
+                let offset = direction.vec() * screen.size();
+                let texture = render_texture(&ugli, &mut self.from_texture, size, from);
+                self.draw2d.textured_quad(
+                    framebuffer,
+                    camera,
+                    screen.translate(-offset * progress),
+                    texture,
+                    Rgba::WHITE,
+                );
+                let texture = render_texture(&ugli, &mut self.to_texture, size, to);
+                self.draw2d.textured_quad(
+                    framebuffer,
+                    camera,
+                    screen.translate(offset * (1.0 - progress)),
+                    texture,
+                    Rgba::WHITE,
+                );
+            }
+            Effect::Wipe(direction) => {
+                from(framebuffer);
+                let texture = render_texture(&ugli, &mut self.to_texture, size, to);
+                // Part of the screen covered by the new state, in texture coordinates
+                let uv = match direction {
+                    Direction::Left => Aabb2::from_corners(vec2(0.0, 0.0), vec2(progress, 1.0)),
+                    Direction::Right => {
+                        Aabb2::from_corners(vec2(1.0 - progress, 0.0), vec2(1.0, 1.0))
+                    }
+                    Direction::Up => Aabb2::from_corners(vec2(0.0, 1.0 - progress), vec2(1.0, 1.0)),
+                    Direction::Down => Aabb2::from_corners(vec2(0.0, 0.0), vec2(1.0, progress)),
+                };
+                draw2d::TexturedQuad::new(
+                    Aabb2 {
+                        min: uv.min * screen.size(),
+                        max: uv.max * screen.size(),
+                    },
+                    texture,
+                )
+                .sub_texture(uv)
+                .draw2d(&self.draw2d, framebuffer, camera);
+            }
+            Effect::CircleWipe => {
+                from(framebuffer);
+                let texture = render_texture(&ugli, &mut self.to_texture, size, to);
+                const SEGMENTS: usize = 64;
+                let center = screen.center();
+                let radius = (screen.size() / 2.0).len() * progress;
+                let vertex = |pos: vec2<f32>| draw2d::TexturedVertex {
+                    a_pos: pos,
+                    a_color: Rgba::WHITE,
+                    a_vt: pos / screen.size(),
+                };
+                let vertices: Vec<_> = std::iter::once(vertex(center))
+                    .chain((0..=SEGMENTS).map(|i| {
+                        let angle = Angle::from_radians(
+                            i as f32 / SEGMENTS as f32 * 2.0 * std::f32::consts::PI,
+                        );
+                        vertex(center + angle.unit_vec() * radius)
+                    }))
+                    .collect();
+                self.draw2d.draw_textured(
+                    framebuffer,
+                    camera,
+                    &vertices,
+                    texture,
+                    Rgba::WHITE,
+                    ugli::DrawMode::TriangleFan,
+                );
+            }
+        }
+    }
+}
@@ -12,3 +12,28 @@ impl AbstractCamera2d for PixelPerfectCamera {
             * mat3::scale(vec2(2.0 / framebuffer_size.x, 2.0 / framebuffer_size.y))
     }
 }
+
+impl PixelPerfectCamera {
+    /// Camera working in logical pixels for given number of physical pixels per logical pixel
+    pub fn logical(scale_factor: f32) -> LogicalPixelCamera {
+        LogicalPixelCamera { scale_factor }
+    }
+}
+
+/// 2d camera used for rendering in logical pixel space, so that the sizes do not depend on screen density.
+///
+/// Same as [PixelPerfectCamera] with all coordinates multiplied by `scale_factor`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LogicalPixelCamera {
+    /// Number of physical pixels per logical pixel
+    pub scale_factor: f32,
+}
+
+impl AbstractCamera2d for LogicalPixelCamera {
+    fn view_matrix(&self) -> mat3<f32> {
+        mat3::scale_uniform(self.scale_factor)
+    }
+    fn projection_matrix(&self, framebuffer_size: vec2<f32>) -> mat3<f32> {
+        PixelPerfectCamera.projection_matrix(framebuffer_size)
+    }
+}
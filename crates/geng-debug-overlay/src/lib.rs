@@ -8,10 +8,14 @@ use std::rc::Rc;
 
 mod console;
 mod fps_counter;
+mod profiler;
+mod time_travel;
 mod touch_simulator;
 
 use console::*;
 use fps_counter::*;
+use profiler::*;
+pub use time_travel::*;
 use touch_simulator::*;
 
 pub struct DebugOverlay {
@@ -21,6 +25,8 @@ pub struct DebugOverlay {
     fps_counter: FpsCounter,
     console: Console,
     touch_simulator: Option<TouchSimulator>,
+    profiler: Option<Profiler>,
+    capture_toggle: bool,
 }
 
 impl DebugOverlay {
@@ -32,6 +38,8 @@ impl DebugOverlay {
             fps_counter: FpsCounter::new(),
             console: Console::new(),
             touch_simulator: None,
+            profiler: None,
+            capture_toggle: false,
         }
     }
 
@@ -51,10 +59,16 @@ impl DebugOverlay {
         if let Some(touch_simulator) = &self.touch_simulator {
             touch_simulator.draw(framebuffer);
         }
+        if let Some(profiler) = &self.profiler {
+            profiler.draw(framebuffer);
+        }
     }
 
     pub fn handle_event(&mut self, event: Event, mut inner_handler: impl FnMut(Event)) {
-        if let Event::KeyPress { key } = event {
+        if let Event::KeyPress {
+            key, repeat: false, ..
+        } = event
+        {
             match key {
                 Key::F3 => {
                     self.show = !self.show;
@@ -68,6 +82,16 @@ impl DebugOverlay {
                     };
                     return;
                 }
+                Key::P if self.window.is_key_pressed(Key::F3) => {
+                    self.show = !self.show;
+                    self.set_profiler_enabled(self.profiler.is_none());
+                    return;
+                }
+                Key::C if self.window.is_key_pressed(Key::F3) => {
+                    self.show = !self.show;
+                    self.capture_toggle = true;
+                    return;
+                }
                 _ => {}
             }
         }
@@ -94,4 +118,22 @@ impl DebugOverlay {
         }
     }
     pub fn fixed_update(&mut self, _delta_time: f64) {}
+
+    /// Turn puffin profiler scopes on/off and show/hide the flamegraph panel (F3+P)
+    pub fn set_profiler_enabled(&mut self, enabled: bool) {
+        if enabled == self.profiler.is_some() {
+            return;
+        }
+        self.profiler = if enabled {
+            Some(Profiler::new(&self.draw2d))
+        } else {
+            puffin::set_scopes_on(false);
+            None
+        };
+    }
+
+    /// Returns whether frame capture was toggled (F3+C) since last call
+    pub fn take_capture_toggle(&mut self) -> bool {
+        std::mem::take(&mut self.capture_toggle)
+    }
 }
@@ -0,0 +1,152 @@
+use super::*;
+
+/// Number of recent frames to look for the slowest one in
+const FRAMES: usize = 120;
+const MAX_DEPTH: usize = 8;
+const ROW_HEIGHT: f32 = 16.0;
+const TEXT_SIZE: f32 = 12.0;
+
+/// Flamegraph of the slowest recent frame recorded by puffin
+pub struct Profiler {
+    draw2d: Rc<draw2d::Helper>,
+    font: geng_font::Font,
+    frame_view: puffin::GlobalFrameView,
+}
+
+impl Profiler {
+    pub fn new(draw2d: &Rc<draw2d::Helper>) -> Self {
+        puffin::set_scopes_on(true);
+        Self {
+            draw2d: draw2d.clone(),
+            font: geng_font::Font::default(draw2d.ugli()),
+            frame_view: puffin::GlobalFrameView::default(),
+        }
+    }
+
+    pub fn draw(&self, framebuffer: &mut ugli::Framebuffer) {
+        let frame_view = self.frame_view.lock();
+        let Some(frame) = frame_view
+            .latest_frames(FRAMES)
+            .max_by_key(|frame| frame.duration_ns())
+        else {
+            return;
+        };
+        let Ok(frame) = frame.unpacked() else {
+            return;
+        };
+        // Only showing the thread we are drawing from, which is the one running the game
+        let thread_name = std::thread::current().name().unwrap_or_default().to_owned();
+        let Some(stream_info) = frame
+            .thread_streams
+            .iter()
+            .find(|(info, _)| info.name == thread_name)
+            .or(frame.thread_streams.iter().next())
+            .map(|(_, stream_info)| stream_info)
+        else {
+            return;
+        };
+
+        let width = framebuffer.size().x as f32;
+        self.draw2d.quad(
+            framebuffer,
+            &geng_camera::PixelPerfectCamera,
+            Aabb2::from_corners(
+                vec2(0.0, 0.0),
+                vec2(width, ROW_HEIGHT * (MAX_DEPTH + 1) as f32),
+            ),
+            Rgba::new(0.0, 0.0, 0.0, 0.7),
+        );
+        self.draw_text(
+            framebuffer,
+            &format!(
+                "Slowest of last {FRAMES} frames: #{} {:.2}ms",
+                frame.frame_index(),
+                frame.duration_ns() as f64 * 1e-6,
+            ),
+            vec2(2.0, 0.0),
+        );
+        let (frame_start, _) = frame.range_ns();
+        let frame_duration = frame.duration_ns().max(1);
+        let ns_to_x =
+            |ns: puffin::NanoSecond| (ns - frame_start) as f32 / frame_duration as f32 * width;
+        self.draw_scopes(
+            framebuffer,
+            frame_view.scope_collection(),
+            &stream_info.stream,
+            0,
+            0,
+            &ns_to_x,
+        );
+    }
+
+    fn draw_scopes(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        scope_collection: &puffin::ScopeCollection,
+        stream: &puffin::Stream,
+        offset: u64,
+        depth: usize,
+        ns_to_x: &dyn Fn(puffin::NanoSecond) -> f32,
+    ) {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        let Ok(reader) = puffin::Reader::with_offset(stream, offset) else {
+            return;
+        };
+        for scope in reader {
+            let Ok(scope) = scope else {
+                return;
+            };
+            let name = scope_collection
+                .fetch_by_id(&scope.id)
+                .map_or("?", |details| details.name().as_ref());
+            let min = vec2(
+                ns_to_x(scope.record.start_ns),
+                (depth + 1) as f32 * ROW_HEIGHT,
+            );
+            let max = vec2(
+                ns_to_x(scope.record.stop_ns()).max(min.x + 1.0),
+                min.y + ROW_HEIGHT - 1.0,
+            );
+            self.draw2d.quad(
+                framebuffer,
+                &geng_camera::PixelPerfectCamera,
+                Aabb2 { min, max },
+                scope_color(name),
+            );
+            let text = format!("{name} {:.2}ms", scope.record.duration_ns as f64 * 1e-6);
+            if self.font.advance(&text) * TEXT_SIZE < max.x - min.x - 4.0 {
+                self.draw_text(framebuffer, &text, min + vec2(2.0, 0.0));
+            }
+            self.draw_scopes(
+                framebuffer,
+                scope_collection,
+                stream,
+                scope.child_begin_position,
+                depth + 1,
+                ns_to_x,
+            );
+        }
+    }
+
+    fn draw_text(&self, framebuffer: &mut ugli::Framebuffer, text: &str, bottom_left: vec2<f32>) {
+        self.font.draw(
+            framebuffer,
+            &geng_camera::PixelPerfectCamera,
+            text,
+            vec2(geng_font::TextAlign::LEFT, geng_font::TextAlign::CENTER),
+            mat3::translate(bottom_left + vec2(0.0, ROW_HEIGHT / 2.0))
+                * mat3::scale_uniform(TEXT_SIZE),
+            Rgba::WHITE,
+        );
+    }
+}
+
+/// Stable color for the scope so that it is easy to follow between frames
+fn scope_color(name: &str) -> Rgba<f32> {
+    let hash = name.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    });
+    Hsla::new((hash % 360) as f32 / 360.0, 0.5, 0.4, 1.0).into()
+}
@@ -0,0 +1,357 @@
+use super::*;
+
+use batbox_diff::Diff;
+use geng_state::State;
+use std::collections::VecDeque;
+
+/// Every this many frames a full snapshot is stored instead of only a delta
+const KEYFRAME_INTERVAL: usize = 60;
+const BAR_HEIGHT: f32 = 24.0;
+const TEXT_SIZE: f32 = 14.0;
+
+struct Frame<T: Diff> {
+    snapshot: Option<T>,
+    /// Delta from the previous frame
+    delta: T::Delta,
+}
+
+/// Last recorded frames, as deltas with a full snapshot every `keyframe_interval` frames
+struct History<T: Diff> {
+    capacity: usize,
+    keyframe_interval: usize,
+    frames: VecDeque<Frame<T>>,
+    /// State at the last recorded frame, to compute the next delta from
+    last_recorded: Option<T>,
+    /// Total number of recorded frames, to place snapshots
+    recorded_frames: usize,
+}
+
+impl<T: Diff> History<T> {
+    fn new(capacity: usize, keyframe_interval: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            keyframe_interval: keyframe_interval.max(1),
+            frames: VecDeque::new(),
+            last_recorded: None,
+            recorded_frames: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    fn record(&mut self, state: &T) {
+        let delta = match &self.last_recorded {
+            Some(last) => last.diff(state),
+            None => state.diff(state),
+        };
+        let snapshot = (self.recorded_frames % self.keyframe_interval == 0).then(|| state.clone());
+        self.recorded_frames += 1;
+        self.frames.push_back(Frame { snapshot, delta });
+        self.last_recorded = Some(state.clone());
+        if self.frames.len() > self.capacity {
+            // First frame always needs a snapshot to reconstruct from,
+            // so it is moved to the next frame before dropping the first one
+            if self.frames[1].snapshot.is_none() {
+                let second = self.reconstruct(1);
+                self.frames[1].snapshot = Some(second);
+            }
+            self.frames.pop_front();
+        }
+    }
+
+    /// Discard frames after the given one, continuing from the state
+    fn truncate(&mut self, len: usize, state: &T) {
+        if len < self.frames.len() {
+            self.frames.truncate(len);
+            self.last_recorded = Some(state.clone());
+        }
+    }
+
+    /// State at given frame, from the closest snapshot before it
+    fn reconstruct(&self, index: usize) -> T {
+        let keyframe = (0..=index)
+            .rev()
+            .find(|&i| self.frames[i].snapshot.is_some())
+            .expect("First frame always has a snapshot");
+        let mut state = self.frames[keyframe].snapshot.clone().unwrap();
+        for frame in self.frames.range(keyframe + 1..=index) {
+            Diff::update(&mut state, &frame.delta);
+        }
+        state
+    }
+}
+
+/// Records state after every [State::fixed_update] to pause, rewind and step through it.
+///
+/// Press F3+T to pause. While paused, Left/Right step one fixed update back/forward,
+/// and the timeline at the bottom can be dragged with the mouse.
+/// Resuming from a rewound frame discards the frames after it.
+pub struct TimeTravel<S: State + Diff> {
+    state: S,
+    window: Window,
+    draw2d: Rc<draw2d::Helper>,
+    font: geng_font::Font,
+    history: History<S>,
+    /// Index of the shown frame while paused
+    paused_at: Option<usize>,
+    scrubbing: bool,
+    last_fixed_delta_time: f64,
+}
+
+impl<S: State + Diff> TimeTravel<S> {
+    /// Record up to `capacity` fixed updates of the state
+    pub fn new(window: &Window, state: S, capacity: usize) -> Self {
+        let draw2d = Rc::new(draw2d::Helper::new(window.ugli(), true));
+        let mut result = Self {
+            font: geng_font::Font::default(window.ugli()),
+            window: window.clone(),
+            draw2d,
+            history: History::new(capacity, KEYFRAME_INTERVAL),
+            paused_at: None,
+            scrubbing: false,
+            last_fixed_delta_time: 0.0,
+            state,
+        };
+        result.record();
+        result
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        self.paused_at = Some(self.history.len() - 1);
+    }
+
+    /// Continue from the shown frame, discarding the frames after it
+    pub fn resume(&mut self) {
+        if let Some(index) = self.paused_at.take() {
+            self.history.truncate(index + 1, &self.state);
+        }
+    }
+
+    fn record(&mut self) {
+        self.history.record(&self.state);
+    }
+
+    fn show(&mut self, index: usize) {
+        let index = index.min(self.history.len() - 1);
+        self.paused_at = Some(index);
+        self.state = self.history.reconstruct(index);
+    }
+
+    fn step_forward(&mut self) {
+        let Some(index) = self.paused_at else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.show(index + 1);
+        } else {
+            // Past the recorded frames, so actually simulate one more
+            self.state.fixed_update(self.last_fixed_delta_time);
+            self.record();
+            self.paused_at = Some(self.history.len() - 1);
+        }
+    }
+
+    fn bar(&self) -> Aabb2<f32> {
+        let width = self.window.size().x as f32;
+        Aabb2::from_corners(vec2(0.0, 0.0), vec2(width, BAR_HEIGHT))
+    }
+
+    fn scrub(&mut self, position: vec2<f64>) {
+        let bar = self.bar();
+        let t = ((position.x as f32 - bar.min.x) / bar.width()).clamp(0.0, 1.0);
+        self.show((t * (self.history.len() - 1) as f32).round() as usize);
+    }
+
+    fn draw_timeline(&self, framebuffer: &mut ugli::Framebuffer, index: usize) {
+        let camera = &geng_camera::PixelPerfectCamera;
+        let bar = self.bar();
+        self.draw2d
+            .quad(framebuffer, camera, bar, Rgba::new(0.0, 0.0, 0.0, 0.7));
+        let filled = self.history.len() as f32 / self.history.capacity as f32;
+        self.draw2d.quad(
+            framebuffer,
+            camera,
+            Aabb2 {
+                max: vec2(bar.min.x + bar.width() * filled, bar.max.y),
+                ..bar
+            },
+            Rgba::new(0.3, 0.3, 0.3, 0.7),
+        );
+        let x = bar.min.x + bar.width() * index as f32 / (self.history.len() - 1).max(1) as f32;
+        self.draw2d.quad(
+            framebuffer,
+            camera,
+            Aabb2::from_corners(vec2(x - 1.0, bar.min.y), vec2(x + 1.0, bar.max.y)),
+            Rgba::WHITE,
+        );
+        self.font.draw(
+            framebuffer,
+            camera,
+            &format!(
+                "Paused at frame {}/{} (F3+T to resume, Left/Right to step)",
+                index + 1,
+                self.history.len(),
+            ),
+            vec2(geng_font::TextAlign::LEFT, geng_font::TextAlign::CENTER),
+            mat3::translate(vec2(4.0, BAR_HEIGHT * 1.5)) * mat3::scale_uniform(TEXT_SIZE),
+            Rgba::WHITE,
+        );
+    }
+}
+
+impl<S: State + Diff> State for TimeTravel<S> {
+    fn update(&mut self, delta_time: f64) {
+        if !self.is_paused() {
+            State::update(&mut self.state, delta_time);
+        }
+    }
+
+    fn fixed_update(&mut self, delta_time: f64) {
+        self.last_fixed_delta_time = delta_time;
+        if !self.is_paused() {
+            self.state.fixed_update(delta_time);
+            self.record();
+        }
+    }
+
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.state.draw(framebuffer);
+        if let Some(index) = self.paused_at {
+            self.draw_timeline(framebuffer, index);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) -> bool {
+        if let Event::KeyPress {
+            key: Key::T,
+            repeat: false,
+            ..
+        } = event
+        {
+            if self.window.is_key_pressed(Key::F3) {
+                if self.is_paused() {
+                    self.resume();
+                } else {
+                    self.pause();
+                }
+                return true;
+            }
+        }
+        let Some(index) = self.paused_at else {
+            return self.state.handle_event(event);
+        };
+        // Paused state does not receive input
+        match event {
+            Event::KeyPress {
+                key: Key::ArrowLeft,
+                ..
+            } => self.show(index.saturating_sub(1)),
+            Event::KeyPress {
+                key: Key::ArrowRight,
+                ..
+            } => self.step_forward(),
+            Event::MousePress {
+                button: MouseButton::Left,
+            } => {
+                if let Some(position) = self.window.cursor_position() {
+                    if self.bar().contains(position.map(|x| x as f32)) {
+                        self.scrubbing = true;
+                        self.scrub(position);
+                    }
+                }
+            }
+            Event::CursorMove { position } if self.scrubbing => self.scrub(position),
+            Event::MouseRelease {
+                button: MouseButton::Left,
+            } => self.scrubbing = false,
+            _ => {}
+        }
+        true
+    }
+
+    fn transition(&mut self) -> Option<geng_state::Transition> {
+        if self.is_paused() {
+            return None;
+        }
+        self.state.transition()
+    }
+
+    fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
+        self.state.ui(cx)
+    }
+
+    fn on_enter(&mut self) {
+        self.state.on_enter();
+    }
+
+    fn on_exit(&mut self) {
+        self.state.on_exit();
+    }
+
+    fn on_pause(&mut self) {
+        self.state.on_pause();
+    }
+
+    fn on_resume(&mut self, result: Option<Box<dyn std::any::Any>>) {
+        self.state.on_resume(result);
+    }
+
+    fn overlay(&self) -> Option<geng_state::OverlayOptions> {
+        self.state.overlay()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconstructs_recorded_frames() {
+        let mut history = History::<i32>::new(10, 3);
+        for value in 0..5 {
+            history.record(&value);
+        }
+        for index in 0..5 {
+            assert_eq!(history.reconstruct(index), index as i32);
+        }
+    }
+
+    #[test]
+    fn keeps_last_frames_over_capacity() {
+        let mut history = History::<i32>::new(5, 3);
+        for value in 0..12 {
+            history.record(&value);
+        }
+        assert_eq!(history.len(), 5);
+        for index in 0..5 {
+            assert_eq!(history.reconstruct(index), 7 + index as i32);
+        }
+    }
+
+    #[test]
+    fn continues_after_truncate() {
+        let mut history = History::<i32>::new(5, 3);
+        for value in 0..8 {
+            history.record(&value);
+        }
+        let rewound = history.reconstruct(1);
+        history.truncate(2, &rewound);
+        for value in 10..16 {
+            history.record(&value);
+        }
+        assert_eq!(history.len(), 5);
+        for index in 0..5 {
+            assert_eq!(history.reconstruct(index), 11 + index as i32);
+        }
+    }
+}
@@ -55,15 +55,19 @@ impl<T: Model, G: geng::State> geng::State for ConnectingState<T, G> {
             Rgba::BLACK,
         );
     }
-    fn handle_event(&mut self, event: geng::Event) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
         if matches!(
             event,
             geng::Event::KeyPress {
-                key: geng::Key::Escape
+                key: geng::Key::Escape,
+                repeat: false,
+                ..
             }
         ) {
             self.transition = Some(geng::state::Transition::Pop);
+            return true;
         }
+        false
     }
     fn transition(&mut self) -> Option<geng::state::Transition> {
         if let Some(connection) = &mut self.connection {
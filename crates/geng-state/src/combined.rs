@@ -1,5 +1,8 @@
 use super::*;
 
+/// Two states drawn on top of each other, `B` being on top.
+///
+/// For more states or different event propagation see [Layers].
 pub struct CombinedState<A, B>(pub A, pub B);
 
 impl<A: State, B: State> State for CombinedState<A, B> {
@@ -13,9 +16,9 @@ impl<A: State, B: State> State for CombinedState<A, B> {
         self.1.fixed_update(delta_time);
     }
 
-    fn handle_event(&mut self, event: Event) {
-        self.0.handle_event(event.clone());
-        self.1.handle_event(event);
+    /// `B` gets the event first, `A` only gets it if `B` did not handle it
+    fn handle_event(&mut self, event: Event) -> bool {
+        self.1.handle_event(event.clone()) || self.0.handle_event(event)
     }
 
     fn transition(&mut self) -> Option<Transition> {
@@ -36,4 +39,29 @@ impl<A: State, B: State> State for CombinedState<A, B> {
         self.0.draw(framebuffer);
         self.1.draw(framebuffer);
     }
+
+    fn on_enter(&mut self) {
+        self.0.on_enter();
+        self.1.on_enter();
+    }
+
+    fn on_exit(&mut self) {
+        self.0.on_exit();
+        self.1.on_exit();
+    }
+
+    fn on_pause(&mut self) {
+        self.0.on_pause();
+        self.1.on_pause();
+    }
+
+    /// The result is only passed to the first state
+    fn on_resume(&mut self, result: Option<Box<dyn Any>>) {
+        self.0.on_resume(result);
+        self.1.on_resume(None);
+    }
+
+    fn overlay(&self) -> Option<OverlayOptions> {
+        self.0.overlay().or_else(|| self.1.overlay())
+    }
 }
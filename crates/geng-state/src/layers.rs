@@ -0,0 +1,134 @@
+use super::*;
+
+/// How events are passed from a layer to the layers below it, see [Layers]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Propagation {
+    /// Pass the event below if this layer did not handle it
+    #[default]
+    Unhandled,
+    /// Always pass the event below, even if it was handled
+    Always,
+    /// Never pass events below, like an input capturing layer
+    Never,
+}
+
+/// Any number of states drawn on top of each other, in order they were added.
+///
+/// The top layer gets events first, and passes them below according to its [Propagation].
+/// All layers are updated and drawn.
+#[derive(Default)]
+pub struct Layers {
+    layers: Vec<(Box<dyn State>, Propagation)>,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add layer on top, passing unhandled events below
+    pub fn with(self, state: impl State) -> Self {
+        self.with_propagation(state, Propagation::Unhandled)
+    }
+
+    /// Add layer on top with given event propagation
+    pub fn with_propagation(mut self, state: impl State, propagation: Propagation) -> Self {
+        self.push(Box::new(state), propagation);
+        self
+    }
+
+    /// Add layer on top with given event propagation
+    pub fn push(&mut self, state: Box<dyn State>, propagation: Propagation) {
+        self.layers.push((state, propagation));
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Layers from bottom to top
+    fn states(&mut self) -> impl Iterator<Item = &mut Box<dyn State>> {
+        self.layers.iter_mut().map(|(state, _)| state)
+    }
+}
+
+impl State for Layers {
+    fn update(&mut self, delta_time: f64) {
+        for state in self.states() {
+            state.update(delta_time);
+        }
+    }
+
+    fn fixed_update(&mut self, delta_time: f64) {
+        for state in self.states() {
+            state.fixed_update(delta_time);
+        }
+    }
+
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        for state in self.states() {
+            state.draw(framebuffer);
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) -> bool {
+        let mut handled = false;
+        for (state, propagation) in self.layers.iter_mut().rev() {
+            handled |= state.handle_event(event.clone());
+            let pass_below = match propagation {
+                Propagation::Unhandled => !handled,
+                Propagation::Always => true,
+                Propagation::Never => false,
+            };
+            if !pass_below {
+                return true;
+            }
+        }
+        handled
+    }
+
+    /// First transition from the top
+    fn transition(&mut self) -> Option<Transition> {
+        self.layers
+            .iter_mut()
+            .rev()
+            .find_map(|(state, _)| state.transition())
+    }
+
+    fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
+        Box::new(ui::stack(self.states().map(|state| state.ui(cx)).collect()))
+    }
+
+    fn on_enter(&mut self) {
+        for state in self.states() {
+            state.on_enter();
+        }
+    }
+
+    fn on_exit(&mut self) {
+        for state in self.states() {
+            state.on_exit();
+        }
+    }
+
+    fn on_pause(&mut self) {
+        for state in self.states() {
+            state.on_pause();
+        }
+    }
+
+    /// The result is only passed to the bottom layer, same as in [CombinedState]
+    fn on_resume(&mut self, mut result: Option<Box<dyn Any>>) {
+        for state in self.states() {
+            state.on_resume(result.take());
+        }
+    }
+
+    fn overlay(&self) -> Option<OverlayOptions> {
+        self.layers.iter().find_map(|(state, _)| state.overlay())
+    }
+}
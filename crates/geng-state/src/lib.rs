@@ -1,21 +1,77 @@
+use geng_async_state as async_state;
 use geng_ui as ui;
 use geng_window::Event;
+use std::any::Any;
+use std::cell::RefCell;
 use std::ops::DerefMut;
 
 mod combined;
+mod layers;
 mod manager;
 
 pub use combined::*;
+pub use layers::*;
 pub use manager::*;
 
+/// Built-in animations shared with [geng_async_state], see [Transition::animated]
+pub use geng_async_state::transitions;
+
 /// Represents a transition between states.
 pub enum Transition {
     /// Pops (removes) the current state from the state stack.
     Pop,
+    /// Pops the current state, passing the result to [State::on_resume] of the state below.
+    PopWith(Box<dyn Any>),
     /// Replaces the current state with another state.
     Switch(Box<dyn State>),
     /// Pushes a new state on the state stack.
     Push(Box<dyn State>),
+    /// Performs the transition while drawing the animation, see [Transition::animated].
+    Animated {
+        transition: Box<Transition>,
+        animation: Box<dyn async_state::Transition>,
+    },
+}
+
+impl Transition {
+    /// Pop the current state with a result, see [Transition::PopWith]
+    pub fn pop_with(result: impl Any) -> Self {
+        Self::PopWith(Box::new(result))
+    }
+
+    /// Animate the transition, for example with [transitions::Animation].
+    ///
+    /// [Manager] draws both old and new states into the animation while it runs,
+    /// only updating the new states and blocking input.
+    pub fn animated(self, animation: impl async_state::Transition + 'static) -> Self {
+        Self::Animated {
+            transition: Box::new(self),
+            animation: Box::new(animation),
+        }
+    }
+}
+
+/// Options of a state drawn over the states below it in [Manager], see [State::overlay].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OverlayOptions {
+    /// Draw states below and show their ui,
+    /// which only gets input if [OverlayOptions::events_below] is set
+    pub draw_below: bool,
+    /// Keep updating states below, including fixed updates
+    pub update_below: bool,
+    /// Pass events to states below after this state
+    pub events_below: bool,
+}
+
+impl Default for OverlayOptions {
+    /// Draw states below, but keep them paused and not receiving events, like a pause menu
+    fn default() -> Self {
+        Self {
+            draw_below: true,
+            update_below: false,
+            events_below: false,
+        }
+    }
 }
 
 /// Represents a state in the game.
@@ -35,8 +91,12 @@ pub trait State: 'static {
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer);
 
     /// Called whenever an event is registered. See [Event] for a full list of possible events.
-    fn handle_event(&mut self, event: Event) {
+    ///
+    /// Returns whether the event was handled, in which case it is not passed
+    /// to the states below, see [Manager] and [Layers].
+    fn handle_event(&mut self, event: Event) -> bool {
         #![allow(unused_variables)]
+        false
     }
 
     /// Called every frame. If returns `Some`, then a transition occurs.
@@ -48,6 +108,28 @@ pub trait State: 'static {
         #![allow(unused_variables)]
         Box::new(ui::Void)
     }
+
+    /// Called by [Manager] when the state is pushed on the stack or switched to.
+    fn on_enter(&mut self) {}
+
+    /// Called by [Manager] when the state is popped or switched from.
+    fn on_exit(&mut self) {}
+
+    /// Called by [Manager] when another state is pushed on top of this one.
+    fn on_pause(&mut self) {}
+
+    /// Called by [Manager] when the state on top of this one is popped.
+    ///
+    /// `result` is passed with [Transition::PopWith], use [Box::downcast] to get the value.
+    fn on_resume(&mut self, result: Option<Box<dyn Any>>) {
+        #![allow(unused_variables)]
+    }
+
+    /// If returns `Some`, the state is an overlay over the states below it in [Manager].
+    /// Otherwise it hides them completely.
+    fn overlay(&self) -> Option<OverlayOptions> {
+        None
+    }
 }
 
 pub struct Empty;
@@ -66,8 +148,8 @@ impl<T: State + ?Sized> State for Box<T> {
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
         <T as State>::draw(self, framebuffer);
     }
-    fn handle_event(&mut self, event: Event) {
-        <T as State>::handle_event(self, event);
+    fn handle_event(&mut self, event: Event) -> bool {
+        <T as State>::handle_event(self, event)
     }
     fn transition(&mut self) -> Option<Transition> {
         <T as State>::transition(self)
@@ -75,4 +157,19 @@ impl<T: State + ?Sized> State for Box<T> {
     fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
         <T as State>::ui(self, cx)
     }
+    fn on_enter(&mut self) {
+        <T as State>::on_enter(self);
+    }
+    fn on_exit(&mut self) {
+        <T as State>::on_exit(self);
+    }
+    fn on_pause(&mut self) {
+        <T as State>::on_pause(self);
+    }
+    fn on_resume(&mut self, result: Option<Box<dyn Any>>) {
+        <T as State>::on_resume(self, result);
+    }
+    fn overlay(&self) -> Option<OverlayOptions> {
+        <T as State>::overlay(self)
+    }
 }
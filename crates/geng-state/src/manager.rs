@@ -1,55 +1,209 @@
 use super::*;
 
+/// Animation of a [Transition::Animated] in progress
+struct RunningAnimation {
+    animation: Box<dyn async_state::Transition>,
+    /// Number of states from the bottom of the stack that were there before the transition
+    from_len: usize,
+    /// State removed by the transition, drawn on top of the old states
+    removed: Option<Box<dyn State>>,
+}
+
 pub struct Manager {
     stack: Vec<Box<dyn State>>,
+    animation: Option<RunningAnimation>,
 }
 
 impl Manager {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { stack: Vec::new() }
+        Self {
+            stack: Vec::new(),
+            animation: None,
+        }
     }
     pub fn switch(&mut self, state: Box<dyn State>) {
-        *self.stack.last_mut().unwrap() = state;
+        self.switch_impl(state);
+    }
+    fn switch_impl(&mut self, mut state: Box<dyn State>) -> Box<dyn State> {
+        let current = self.stack.last_mut().unwrap();
+        current.on_exit();
+        state.on_enter();
+        std::mem::replace(current, state)
     }
-    pub fn push(&mut self, state: Box<dyn State>) {
+    pub fn push(&mut self, mut state: Box<dyn State>) {
+        if let Some(current) = self.stack.last_mut() {
+            current.on_pause();
+        }
+        state.on_enter();
         self.stack.push(state);
     }
     pub fn pop(&mut self) {
-        self.stack.pop();
+        self.pop_impl(None);
+    }
+    /// Pop current state, passing the result to the state below, see [Transition::PopWith]
+    pub fn pop_with(&mut self, result: Box<dyn Any>) {
+        self.pop_impl(Some(result));
+    }
+    fn pop_impl(&mut self, result: Option<Box<dyn Any>>) -> Option<Box<dyn State>> {
+        let mut state = self.stack.pop()?;
+        state.on_exit();
+        if let Some(current) = self.stack.last_mut() {
+            current.on_resume(result);
+        }
+        Some(state)
     }
     pub fn current_state(&mut self) -> Option<&mut dyn State> {
         self.stack.last_mut().map(|state| state.deref_mut())
     }
+    /// Whether an animated transition is in progress
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Pop => self.pop(),
+            Transition::PopWith(result) => self.pop_with(result),
+            Transition::Push(state) => self.push(state),
+            Transition::Switch(state) => self.switch(state),
+            Transition::Animated {
+                transition,
+                animation,
+            } => {
+                // Only the outermost animation is used
+                let mut transition = *transition;
+                while let Transition::Animated {
+                    transition: inner, ..
+                } = transition
+                {
+                    transition = *inner;
+                }
+                let len = self.stack.len();
+                let (from_len, removed) = match transition {
+                    Transition::Pop => (len.saturating_sub(1), self.pop_impl(None)),
+                    Transition::PopWith(result) => {
+                        (len.saturating_sub(1), self.pop_impl(Some(result)))
+                    }
+                    Transition::Push(state) => {
+                        self.push(state);
+                        (len, None)
+                    }
+                    Transition::Switch(state) => (len - 1, Some(self.switch_impl(state))),
+                    Transition::Animated { .. } => unreachable!(),
+                };
+                self.animation = Some(RunningAnimation {
+                    animation,
+                    from_len,
+                    removed,
+                });
+            }
+        }
+    }
+}
+
+/// Index of the lowest state that is reached from the top of the stack
+/// through overlays that allow it
+fn first_reached(
+    overlays: &[Option<OverlayOptions>],
+    allow: impl Fn(&OverlayOptions) -> bool,
+) -> usize {
+    let mut first = overlays.len().saturating_sub(1);
+    while first > 0 {
+        match &overlays[first] {
+            Some(options) if allow(options) => first -= 1,
+            _ => break,
+        }
+    }
+    first
+}
+
+fn overlays(states: &[Box<dyn State>]) -> Vec<Option<OverlayOptions>> {
+    states.iter().map(|state| state.overlay()).collect()
+}
+
+fn draw_states(states: &mut [&mut dyn State], framebuffer: &mut ugli::Framebuffer) {
+    let overlays: Vec<_> = states.iter().map(|state| state.overlay()).collect();
+    let first = first_reached(&overlays, |options| options.draw_below);
+    for state in &mut states[first..] {
+        state.draw(framebuffer);
+    }
 }
 
 impl State for Manager {
     fn update(&mut self, delta_time: f64) {
-        if let Some(state) = self.current_state() {
-            state.update(delta_time);
-            if let Some(transition) = state.transition() {
-                match transition {
-                    Transition::Pop => self.pop(),
-                    Transition::Push(state) => self.push(state),
-                    Transition::Switch(state) => self.switch(state),
-                }
+        if let Some(animation) = &mut self.animation {
+            animation.animation.update(delta_time);
+            if animation.animation.finished() {
+                self.animation = None;
             }
         }
+        let first = first_reached(&overlays(&self.stack), |options| options.update_below);
+        for state in self.stack.iter_mut().skip(first) {
+            state.update(delta_time);
+        }
+        if self.animation.is_some() {
+            return;
+        }
+        if let Some(transition) = self.current_state().and_then(|state| state.transition()) {
+            self.apply(transition);
+        }
     }
     fn fixed_update(&mut self, delta_time: f64) {
-        if let Some(state) = self.current_state() {
+        let first = first_reached(&overlays(&self.stack), |options| options.update_below);
+        for state in self.stack.iter_mut().skip(first) {
             state.fixed_update(delta_time);
         }
     }
-    fn handle_event(&mut self, event: Event) {
-        if let Some(state) = self.current_state() {
-            state.handle_event(event);
+    fn handle_event(&mut self, event: Event) -> bool {
+        // Input is blocked during animations
+        if self.animation.is_some() {
+            return true;
         }
+        // Top state gets the event first
+        let first = first_reached(&overlays(&self.stack), |options| options.events_below);
+        self.stack
+            .iter_mut()
+            .skip(first)
+            .rev()
+            .any(|state| state.handle_event(event.clone()))
     }
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
-        if let Some(state) = self.current_state() {
-            state.draw(framebuffer);
-        }
+        let Some(RunningAnimation {
+            animation,
+            from_len,
+            removed,
+        }) = &mut self.animation
+        else {
+            let mut states: Vec<&mut dyn State> = self
+                .stack
+                .iter_mut()
+                .map(|state| state.deref_mut())
+                .collect();
+            draw_states(&mut states, framebuffer);
+            return;
+        };
+        let stack = RefCell::new(&mut self.stack);
+        animation.draw(
+            &mut |framebuffer| {
+                let mut stack = stack.borrow_mut();
+                let from_len = (*from_len).min(stack.len());
+                let mut states: Vec<&mut dyn State> = stack[..from_len]
+                    .iter_mut()
+                    .map(|state| state.deref_mut())
+                    .collect();
+                if let Some(removed) = removed.as_mut() {
+                    states.push(removed.deref_mut());
+                }
+                draw_states(&mut states, framebuffer);
+            },
+            &mut |framebuffer| {
+                let mut stack = stack.borrow_mut();
+                let mut states: Vec<&mut dyn State> =
+                    stack.iter_mut().map(|state| state.deref_mut()).collect();
+                draw_states(&mut states, framebuffer);
+            },
+            framebuffer,
+        );
     }
     fn transition(&mut self) -> Option<Transition> {
         if self.stack.is_empty() {
@@ -59,9 +213,28 @@ impl State for Manager {
         }
     }
     fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
-        match self.current_state() {
-            Some(state) => state.ui(cx),
-            None => Box::new(ui::Void),
+        // Ui is hidden during animations since it is not drawn into them
+        if self.animation.is_some() {
+            return Box::new(ui::Void);
         }
+        let overlays = overlays(&self.stack);
+        let first = first_reached(&overlays, |options| options.draw_below);
+        // Ui of states not receiving events is still shown, but does not get input either
+        let first_with_events = first_reached(&overlays, |options| options.events_below);
+        Box::new(ui::stack(
+            self.stack
+                .iter_mut()
+                .enumerate()
+                .skip(first)
+                .map(|(index, state)| -> Box<dyn ui::Widget + 'a> {
+                    let ui = state.ui(cx);
+                    if index < first_with_events {
+                        Box::new(ui::IgnoreInput::new(ui))
+                    } else {
+                        ui
+                    }
+                })
+                .collect(),
+        ))
     }
 }
@@ -57,8 +57,10 @@ impl LayoutContext<'_> {
 
 pub struct Controller {
     target_ui_resolution: Option<vec2<f64>>,
+    scale_factor: f64,
     draw2d: draw2d::Helper,
     theme: Theme,
+    translate: Option<Box<dyn Fn(&str) -> String>>,
     state: RefCell<State>,
 }
 
@@ -66,8 +68,10 @@ impl Controller {
     pub fn new(ugli: &Ugli, theme: Theme, target_ui_resolution: Option<vec2<f64>>) -> Self {
         Self {
             target_ui_resolution,
+            scale_factor: 1.0,
             draw2d: draw2d::Helper::new(ugli, true),
             theme,
+            translate: None,
             state: RefCell::new(State {
                 size: vec2(1.0, 1.0),
                 scale: 1.0,
@@ -86,6 +90,26 @@ impl Controller {
     pub fn theme(&self) -> &Theme {
         &self.theme
     }
+    /// Set number of physical pixels per logical pixel, so that ui is laid out in logical pixels.
+    ///
+    /// Not used if target ui resolution is set
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+    /// Set function used to translate localization keys, see [Controller::translate]
+    pub fn set_translate(&mut self, translate: impl Fn(&str) -> String + 'static) {
+        self.translate = Some(Box::new(translate));
+    }
+    /// Translate localization key into current language, or return the key if no translation was set
+    pub fn translate(&self, key: &str) -> String {
+        match &self.translate {
+            Some(translate) => translate(key),
+            None => key.to_owned(),
+        }
+    }
     pub fn get_state<T: Default + 'static>(&self) -> &mut T {
         self.get_state_with(T::default)
     }
@@ -110,10 +134,12 @@ impl Controller {
 
 impl Controller {
     pub fn update(&self, root: &mut dyn Widget, delta_time: f64) {
+        puffin::profile_function!();
         self.layout(root);
         traverse_mut(root, &mut |widget| widget.update(delta_time), &mut |_| {});
     }
     fn layout(&self, root: &mut dyn Widget) {
+        puffin::profile_function!();
         let mut state = self.state.borrow_mut();
         let state = state.deref_mut();
         state.constraints.clear();
@@ -148,6 +174,7 @@ impl Controller {
         state.next_state = 0;
     }
     pub fn draw(&self, root: &mut dyn Widget, framebuffer: &mut ugli::Framebuffer) {
+        puffin::profile_function!();
         {
             let mut state = self.state.borrow_mut();
             let framebuffer_size = framebuffer.size().map(|x| x as f64);
@@ -155,7 +182,7 @@ impl Controller {
                 Some(target_size) => {
                     (framebuffer_size.x / target_size.x).max(framebuffer_size.y / target_size.y)
                 }
-                None => 1.0,
+                None => self.scale_factor,
             };
             state.size = framebuffer_size / state.scale;
         }
@@ -175,11 +202,12 @@ impl Controller {
         );
     }
     pub fn handle_event(&self, root: &mut dyn Widget, event: Event) -> bool {
+        puffin::profile_function!();
         let event = &event;
         self.layout(root);
         let mut state = self.state.borrow_mut();
         let mut captured = false;
-        traverse_mut(
+        traverse_input_mut(
             root,
             &mut |widget| {
                 let widget_position = state.get_position(widget);
@@ -0,0 +1,38 @@
+use super::*;
+
+/// Widget drawn as usual, but not receiving any input, see [Widget::receives_input]
+pub struct IgnoreInput<T> {
+    child: T,
+}
+
+impl<T> IgnoreInput<T> {
+    pub fn new(child: T) -> Self {
+        Self { child }
+    }
+}
+
+mod ext {
+    use super::*;
+
+    pub trait WidgetExt: Widget + Sized {
+        fn ignore_input(self) -> IgnoreInput<Self> {
+            IgnoreInput::new(self)
+        }
+    }
+
+    impl<T: Widget> WidgetExt for T {}
+}
+
+pub use ext::WidgetExt as _;
+
+impl<T: Widget> Widget for IgnoreInput<T> {
+    fn receives_input(&self) -> bool {
+        false
+    }
+    fn calc_constraints(&mut self, children: &ConstraintsContext) -> Constraints {
+        children.get_constraints(&self.child)
+    }
+    fn walk_children_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        f(&mut self.child);
+    }
+}
@@ -3,6 +3,7 @@ use super::*;
 mod align;
 mod column;
 mod constraint_override;
+mod ignore_input;
 mod padding;
 mod row;
 mod shrink;
@@ -11,6 +12,7 @@ mod stack;
 pub use align::*;
 pub use column::*;
 pub use constraint_override::*;
+pub use ignore_input::*;
 pub use padding::*;
 pub use row::*;
 pub use shrink::*;
@@ -38,3 +38,17 @@ fn traverse_mut(
     widget.walk_children_mut(&mut |widget| traverse_mut(widget, on_enter, on_leave));
     on_leave(widget);
 }
+
+/// Same as [traverse_mut], but skipping widgets that do not receive input
+fn traverse_input_mut(
+    widget: &mut dyn Widget,
+    on_enter: &mut dyn FnMut(&mut dyn Widget),
+    on_leave: &mut dyn FnMut(&mut dyn Widget),
+) {
+    if !widget.receives_input() {
+        return;
+    }
+    on_enter(widget);
+    widget.walk_children_mut(&mut |widget| traverse_input_mut(widget, on_enter, on_leave));
+    on_leave(widget);
+}
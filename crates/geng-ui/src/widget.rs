@@ -95,6 +95,10 @@ pub trait Widget {
     fn sense(&mut self) -> Option<&mut Sense> {
         None
     }
+    /// Whether input is passed to this widget and its children
+    fn receives_input(&self) -> bool {
+        true
+    }
     fn update(&mut self, delta_time: f64) {
         #![allow(unused_variables)]
     }
@@ -43,6 +43,10 @@ impl<'a> Button<'a> {
             f: None,
         }
     }
+    /// Button with text translated from the localization key, see [Controller::translate]
+    pub fn localized(cx: &'a Controller, key: &str) -> Self {
+        Self::new(cx, &cx.translate(key))
+    }
     pub fn was_clicked(&self) -> bool {
         self.clicked
     }
@@ -18,6 +18,13 @@ impl<T: AsRef<str>, F: AsRef<Font>> Text<T, F> {
     }
 }
 
+impl<F: AsRef<Font>> Text<String, F> {
+    /// Text translated from the localization key, see [Controller::translate]
+    pub fn localized(cx: &Controller, key: &str, font: F, size: f32, color: Rgba<f32>) -> Self {
+        Self::new(cx.translate(key), font, size, color)
+    }
+}
+
 impl<T: AsRef<str>, F: AsRef<Font>> Widget for Text<T, F> {
     fn calc_constraints(&mut self, cx: &ConstraintsContext) -> Constraints {
         calc_text_constraints(self.text.as_ref(), self.font.as_ref(), self.size, cx)
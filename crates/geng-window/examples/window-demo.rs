@@ -99,7 +99,9 @@ async fn space_escape(depth: usize, renderer: &Renderer, window: &window::Window
         mat3::rotate(thread_rng().gen()) * mat3::scale_uniform((depth as f32 * 0.1).exp());
     while let Some(event) = window.events().next().await {
         match event {
-            window::Event::KeyPress { key } => match key {
+            window::Event::KeyPress {
+                key, repeat: false, ..
+            } => match key {
                 window::Key::Escape => {
                     break;
                 }
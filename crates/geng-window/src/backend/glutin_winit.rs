@@ -16,27 +16,73 @@ pub struct Context {
     ugli: Ugli,
     context_size: Cell<vec2<usize>>,
     edited_text: RefCell<Option<String>>,
+    /// Whether input method is composing text, so key presses are handled by it
+    composing: Cell<bool>,
+    /// Created on first use, and kept alive since on Linux the copied text is owned by it
+    #[cfg(not(target_os = "android"))]
+    clipboard: RefCell<Option<arboard::Clipboard>>,
+    modifiers: Cell<Modifiers>,
     cursor_source: RefCell<Option<winit::window::CustomCursorSource>>,
+    vsync: Cell<bool>,
+    max_fps: Cell<Option<f64>>,
+    last_frame: Cell<std::time::Instant>,
+    /// Set when next redraw is delayed by the frame rate limiter
+    next_frame: Cell<Option<std::time::Instant>>,
+}
+
+fn physical_size(size: vec2<usize>) -> winit::dpi::PhysicalSize<u32> {
+    winit::dpi::PhysicalSize {
+        width: size.x as u32,
+        height: size.y as u32,
+    }
 }
 
 fn create_window_attributes(options: &Options) -> winit::window::WindowAttributes {
     let mut attributes = winit::window::Window::default_attributes();
     if let Some(size) = options.size {
-        attributes = attributes.with_inner_size(winit::dpi::PhysicalSize {
-            width: size.x as u32,
-            height: size.y as u32,
+        attributes = attributes.with_inner_size(physical_size(size));
+    }
+    if let Some(position) = options.position {
+        attributes = attributes.with_position(winit::dpi::PhysicalPosition {
+            x: position.x,
+            y: position.y,
         });
     }
+    if let Some(size) = options.min_size {
+        attributes = attributes.with_min_inner_size(physical_size(size));
+    }
+    if let Some(size) = options.max_size {
+        attributes = attributes.with_max_inner_size(physical_size(size));
+    }
+    attributes = attributes.with_resizable(options.resizable);
+    attributes = attributes.with_maximized(options.maximized);
     attributes = attributes.with_title(&options.title);
     attributes = attributes.with_transparent(options.transparency);
     attributes = attributes.with_visible(!options.start_hidden);
     attributes
 }
 
+fn is_wayland(event_loop: &winit::event_loop::ActiveEventLoop) -> bool {
+    matches!(
+        event_loop.display_handle().unwrap().as_raw(),
+        raw_window_handle::RawDisplayHandle::Wayland(_)
+    )
+}
+
+fn swap_interval(vsync: bool, wayland: bool) -> glutin::surface::SwapInterval {
+    if vsync && !wayland {
+        glutin::surface::SwapInterval::Wait(1.try_into().unwrap())
+    } else {
+        // on wayland need pre_present_notify instead
+        glutin::surface::SwapInterval::DontWait
+    }
+}
+
 fn resume(
     window_field: &mut Option<winit::window::Window>,
     event_loop: &winit::event_loop::ActiveEventLoop,
     options: &Options,
+    vsync: bool,
     gl_ctx_field: &mut Option<glutin::context::PossiblyCurrentContext>,
     gl_surface_field: &mut Option<glutin::surface::Surface<glutin::surface::WindowSurface>>,
 ) {
@@ -65,13 +111,7 @@ fn resume(
     if let Err(res) = glutin::surface::GlSurface::set_swap_interval(
         &gl_surface,
         gl_ctx,
-        match event_loop.display_handle().unwrap().as_raw() {
-            raw_window_handle::RawDisplayHandle::Wayland(_) => {
-                // on wayland need pre_present_notify
-                glutin::surface::SwapInterval::DontWait
-            }
-            _ => glutin::surface::SwapInterval::Wait(1.try_into().unwrap()),
-        },
+        swap_interval(vsync, is_wayland(event_loop)),
     ) {
         log::error!("Error setting vsync: {res:?}");
     }
@@ -121,8 +161,14 @@ where
                 event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
             }
             if let Some(context) = &mut self.context {
+                if !matches!(event, winit::event::Event::Suspended) {
+                    context.limit_frame_rate(event_loop);
+                }
                 context.handle_winit_event(event, event_loop, &mut |event| {
-                    if let Event::KeyPress { key: Key::Escape } = event {
+                    if let Event::KeyPress {
+                        key: Key::Escape, ..
+                    } = event
+                    {
                         context.unlock_cursor();
                     }
                     if self.event_handler.as_mut().unwrap()(event).is_break() {
@@ -203,6 +249,7 @@ where
                     &mut window,
                     event_loop,
                     &self.options,
+                    self.options.vsync,
                     &mut gl_ctx,
                     &mut gl_surface,
                 );
@@ -214,10 +261,7 @@ where
                     )
                 });
                 let context = Rc::new(Context {
-                    should_pre_present_notify: matches!(
-                        event_loop.display_handle().unwrap().as_raw(),
-                        raw_window_handle::RawDisplayHandle::Wayland(_)
-                    ),
+                    should_pre_present_notify: is_wayland(event_loop),
                     options: self.options.clone(),
                     window: RefCell::new(window),
                     gl_surface: RefCell::new(gl_surface),
@@ -228,7 +272,15 @@ where
                     cursor_pos: Cell::new(vec2(0.0, 0.0)),
                     context_size: Cell::new(vec2(1, 1)),
                     edited_text: RefCell::new(None),
+                    composing: Cell::new(false),
+                    #[cfg(not(target_os = "android"))]
+                    clipboard: RefCell::new(None),
+                    modifiers: Cell::new(Modifiers::default()),
                     cursor_source: RefCell::new(None),
+                    vsync: Cell::new(self.options.vsync),
+                    max_fps: Cell::new(None),
+                    last_frame: Cell::new(std::time::Instant::now()),
+                    next_frame: Cell::new(None),
                 });
                 self.event_handler = Some((self.once_ready.take().unwrap())(context.clone()));
                 self.context = Some(context);
@@ -271,6 +323,92 @@ impl Context {
         vec2(width as usize, height as usize)
     }
 
+    pub fn scale_factor(&self) -> f64 {
+        match &*self.window.borrow() {
+            Some(window) => window.scale_factor(),
+            None => 1.0,
+        }
+    }
+
+    pub fn position(&self) -> Option<vec2<i32>> {
+        let position = self.window.borrow().as_ref()?.outer_position().ok()?;
+        Some(vec2(position.x, position.y))
+    }
+
+    pub fn set_title(&self, title: &str) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_title(title);
+    }
+
+    pub fn set_size(&self, size: vec2<usize>) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        // Resized event will be sent if the size actually changes
+        let _ = window.request_inner_size(physical_size(size));
+    }
+
+    pub fn set_position(&self, position: vec2<i32>) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_outer_position(winit::dpi::PhysicalPosition {
+            x: position.x,
+            y: position.y,
+        });
+    }
+
+    pub fn set_min_size(&self, min_size: Option<vec2<usize>>) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_min_inner_size(min_size.map(physical_size));
+    }
+
+    pub fn set_max_size(&self, max_size: Option<vec2<usize>>) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_max_inner_size(max_size.map(physical_size));
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_resizable(resizable);
+    }
+
+    pub fn is_resizable(&self) -> bool {
+        match &*self.window.borrow() {
+            Some(window) => window.is_resizable(),
+            None => self.options.resizable,
+        }
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_maximized(maximized);
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        match &*self.window.borrow() {
+            Some(window) => window.is_maximized(),
+            None => false,
+        }
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        window.set_minimized(minimized);
+    }
+
     pub fn set_fullscreen(&self, fullscreen: bool) {
         let Some(window) = &*self.window.borrow() else {
             return;
@@ -416,16 +554,67 @@ impl Context {
                     });
                 }
             }
+            winit::event::WindowEvent::Ime(ime) => match ime {
+                winit::event::Ime::Preedit(text, cursor) => {
+                    self.composing.set(!text.is_empty());
+                    event_handler(Event::TextComposition {
+                        text,
+                        cursor_range: cursor.map(|(start, end)| start..end),
+                    });
+                }
+                winit::event::Ime::Commit(text) => {
+                    self.composing.set(false);
+                    let mut edited_text_ref = self.edited_text.borrow_mut();
+                    if let Some(edited_text) = edited_text_ref.deref_mut() {
+                        edited_text.push_str(&text);
+                        let event = Event::EditText(edited_text.clone());
+                        std::mem::drop(edited_text_ref);
+                        event_handler(event);
+                    }
+                }
+                winit::event::Ime::Enabled | winit::event::Ime::Disabled => {
+                    self.composing.set(false);
+                }
+            },
+            winit::event::WindowEvent::ModifiersChanged(modifiers) => {
+                let state = modifiers.state();
+                self.modifiers.set(Modifiers {
+                    shift: state.shift_key(),
+                    ctrl: state.control_key(),
+                    alt: state.alt_key(),
+                    logo: state.super_key(),
+                });
+            }
             winit::event::WindowEvent::KeyboardInput { event, .. } => {
                 {
                     let mut edited_text_ref = self.edited_text.borrow_mut();
                     if let Some(edited_text) = edited_text_ref.deref_mut() {
-                        if event.state == winit::event::ElementState::Pressed {
+                        if event.state == winit::event::ElementState::Pressed
+                            && !self.composing.get()
+                        {
                             if event.physical_key == winit::keyboard::KeyCode::Backspace {
                                 edited_text.pop();
                                 let event = Event::EditText(edited_text.clone());
                                 std::mem::drop(edited_text_ref);
                                 event_handler(event);
+                            } else if self.modifiers.get().command()
+                                && event.physical_key == winit::keyboard::KeyCode::KeyV
+                            {
+                                match self.read_clipboard() {
+                                    Ok(text) => {
+                                        edited_text
+                                            .extend(text.chars().filter(|c| !c.is_control()));
+                                        let event = Event::EditText(edited_text.clone());
+                                        std::mem::drop(edited_text_ref);
+                                        event_handler(event);
+                                    }
+                                    Err(e) => log::error!("Failed to paste: {e}"),
+                                }
+                            } else if self.modifiers.get().command()
+                                && event.physical_key == winit::keyboard::KeyCode::KeyC
+                            {
+                                // No selection, so copying the whole text
+                                self.set_clipboard_text(edited_text);
                             } else {
                                 #[cfg(not(target_os = "android"))]
                                 {
@@ -449,9 +638,30 @@ impl Context {
                     }
                 }
                 if let Some(key) = from_winit_key(event.physical_key) {
+                    let modifiers = self.modifiers.get();
                     event_handler(match event.state {
-                        winit::event::ElementState::Pressed => Event::KeyPress { key },
-                        winit::event::ElementState::Released => Event::KeyRelease { key },
+                        winit::event::ElementState::Pressed => {
+                            #[cfg(not(target_os = "android"))]
+                            let logical_key = {
+                                use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
+                                event.key_without_modifiers()
+                            };
+                            #[cfg(target_os = "android")]
+                            let logical_key = event.logical_key.clone();
+                            Event::KeyPress {
+                                key,
+                                repeat: event.repeat,
+                                modifiers,
+                                logical_key: match logical_key {
+                                    winit::keyboard::Key::Character(c) => Some(c.to_string()),
+                                    _ => None,
+                                },
+                                text: event.text.map(|text| text.to_string()),
+                            }
+                        }
+                        winit::event::ElementState::Released => {
+                            Event::KeyRelease { key, modifiers }
+                        }
                     });
                 }
             }
@@ -468,8 +678,36 @@ impl Context {
                         self.context_size
                             .set(vec2(new_size.width, new_size.height).map(|x| x as usize));
                     }
+                    event_handler(Event::Resized(
+                        vec2(new_size.width, new_size.height).map(|x| x as usize),
+                    ));
                 }
             }
+            winit::event::WindowEvent::Moved(position) => {
+                event_handler(Event::Moved(vec2(position.x, position.y)));
+            }
+            winit::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                event_handler(Event::ScaleFactorChanged(scale_factor));
+            }
+            winit::event::WindowEvent::Occluded(occluded) => {
+                event_handler(Event::Occluded(occluded));
+            }
+            // Cursor position is not updated while dragging, but is the last known one
+            winit::event::WindowEvent::HoveredFile(path) => {
+                event_handler(Event::FileHovered {
+                    path,
+                    position: self.cursor_pos.get(),
+                });
+            }
+            winit::event::WindowEvent::HoveredFileCancelled => {
+                event_handler(Event::FileHoverCancelled);
+            }
+            winit::event::WindowEvent::DroppedFile(path) => {
+                event_handler(Event::FileDropped {
+                    path,
+                    position: self.cursor_pos.get(),
+                });
+            }
             winit::event::WindowEvent::Touch(touch) => {
                 let geng_touch = Touch {
                     id: touch.id,
@@ -484,10 +722,15 @@ impl Context {
                 });
             }
             winit::event::WindowEvent::RedrawRequested => {
+                if self.next_frame.get().is_some() {
+                    // Redraw was requested by the system, but we are still waiting
+                    return;
+                }
+                self.last_frame.set(std::time::Instant::now());
                 if let Some(gl_surface) = &*self.gl_surface.borrow() {
                     event_handler(Event::Draw);
                     if let Some(window) = self.window.borrow().as_ref() {
-                        if self.options.vsync && self.should_pre_present_notify {
+                        if self.vsync.get() && self.should_pre_present_notify {
                             window.pre_present_notify();
                         }
                     }
@@ -497,6 +740,14 @@ impl Context {
                     )
                     .unwrap();
                 }
+                if let Some(max_fps) = self.max_fps.get() {
+                    let next_frame =
+                        self.last_frame.get() + std::time::Duration::from_secs_f64(1.0 / max_fps);
+                    if std::time::Instant::now() < next_frame {
+                        self.next_frame.set(Some(next_frame));
+                        return;
+                    }
+                }
                 if let Some(window) = self.window.borrow().as_ref() {
                     window.request_redraw();
                 }
@@ -505,6 +756,21 @@ impl Context {
         }
     }
 
+    /// Request delayed redraw if its time, or wait until it is
+    fn limit_frame_rate(&self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let Some(next_frame) = self.next_frame.get() else {
+            return;
+        };
+        if std::time::Instant::now() < next_frame {
+            event_loop.set_control_flow(winit::event_loop::ControlFlow::WaitUntil(next_frame));
+            return;
+        }
+        self.next_frame.set(None);
+        if let Some(window) = self.window.borrow().as_ref() {
+            window.request_redraw();
+        }
+    }
+
     fn handle_winit_event(
         &self,
         event: winit::event::Event<()>,
@@ -529,6 +795,7 @@ impl Context {
                         &mut self.window.borrow_mut(),
                         event_loop,
                         &self.options,
+                        self.vsync.get(),
                         &mut self.gl_ctx.borrow_mut(),
                         &mut self.gl_surface.borrow_mut(),
                     );
@@ -563,12 +830,19 @@ impl Context {
 
     pub fn start_text_edit(&self, text: &str) {
         *self.edited_text.borrow_mut() = Some(text.to_owned());
+        if let Some(window) = &*self.window.borrow() {
+            window.set_ime_allowed(true);
+        }
         #[cfg(target_os = "android")]
         batbox_android::app().show_soft_input(true);
     }
 
     pub fn stop_text_edit(&self) {
         *self.edited_text.borrow_mut() = None;
+        self.composing.set(false);
+        if let Some(window) = &*self.window.borrow() {
+            window.set_ime_allowed(false);
+        }
         #[cfg(target_os = "android")]
         batbox_android::app().hide_soft_input(false);
     }
@@ -577,11 +851,92 @@ impl Context {
         self.edited_text.borrow().is_some()
     }
 
+    pub fn set_text_edit_area(&self, area: Aabb2<f64>) {
+        let Some(window) = &*self.window.borrow() else {
+            return;
+        };
+        // Window coordinates are from the top left corner
+        window.set_ime_cursor_area(
+            winit::dpi::PhysicalPosition {
+                x: area.min.x,
+                y: self.real_size().y as f64 - area.max.y,
+            },
+            winit::dpi::PhysicalSize {
+                width: area.width(),
+                height: area.height(),
+            },
+        );
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn with_clipboard<T>(
+        &self,
+        f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    ) -> anyhow::Result<T> {
+        let mut clipboard = self.clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new()?);
+        }
+        Ok(f(clipboard.as_mut().unwrap())?)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    fn read_clipboard(&self) -> anyhow::Result<String> {
+        self.with_clipboard(|clipboard| clipboard.get_text())
+    }
+
+    #[cfg(target_os = "android")]
+    fn read_clipboard(&self) -> anyhow::Result<String> {
+        anyhow::bail!("Clipboard is not supported on android")
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) {
+        #[cfg(not(target_os = "android"))]
+        if let Err(e) = self.with_clipboard(|clipboard| clipboard.set_text(text)) {
+            log::error!("Failed to write to clipboard: {e}");
+        }
+        #[cfg(target_os = "android")]
+        log::error!("Clipboard is not supported on android: {text:?} was not copied");
+    }
+
+    pub fn clipboard_text(&self) -> future::LocalBoxFuture<'static, anyhow::Result<String>> {
+        future::ready(self.read_clipboard()).boxed_local()
+    }
+
     pub fn show(&self) {
         if let Some(window) = &mut *self.window.borrow_mut() {
             window.set_visible(true);
         }
     }
+
+    pub fn set_vsync(&self, vsync: bool) {
+        self.vsync.set(vsync);
+        let gl_surface = self.gl_surface.borrow();
+        let gl_ctx = self.gl_ctx.borrow();
+        let (Some(gl_surface), Some(gl_ctx)) = (gl_surface.as_ref(), gl_ctx.as_ref()) else {
+            // Will be applied on resume
+            return;
+        };
+        if let Err(e) = glutin::surface::GlSurface::set_swap_interval(
+            gl_surface,
+            gl_ctx,
+            swap_interval(vsync, self.should_pre_present_notify),
+        ) {
+            log::error!("Error setting vsync: {e:?}");
+        }
+    }
+
+    pub fn vsync(&self) -> bool {
+        self.vsync.get()
+    }
+
+    pub fn set_max_fps(&self, max_fps: Option<f64>) {
+        self.max_fps.set(max_fps);
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.max_fps.get()
+    }
 }
 
 fn from_winit_key(key: winit::keyboard::PhysicalKey) -> Option<Key> {
@@ -0,0 +1,215 @@
+use super::*;
+
+use anyhow::Context as _;
+use glutin::api::egl;
+use glutin::prelude::*;
+
+const DEFAULT_SIZE: vec2<usize> = vec2(800, 600);
+
+pub struct Context {
+    size: vec2<usize>,
+    ugli: Ugli,
+    color: RefCell<ugli::Texture>,
+    depth: RefCell<ugli::Renderbuffer<ugli::DepthStencilValue>>,
+    is_fullscreen: Cell<bool>,
+    lock_cursor: Cell<bool>,
+    edited_text: RefCell<Option<String>>,
+    /// Clipboard only shared within the process
+    clipboard: RefCell<String>,
+    vsync: Cell<bool>,
+    max_fps: Cell<Option<f64>>,
+    // Must be dropped after all the gl resources
+    _gl_ctx: egl::context::PossiblyCurrentContext,
+}
+
+fn create_gl_context(
+    device: &egl::device::Device,
+) -> anyhow::Result<(egl::display::Display, egl::context::PossiblyCurrentContext)> {
+    let display = unsafe { egl::display::Display::with_device(device, None) }?;
+    let template = glutin::config::ConfigTemplateBuilder::new()
+        .with_alpha_size(8)
+        .with_surface_type(glutin::config::ConfigSurfaceTypes::empty())
+        .build();
+    let config = unsafe { display.find_configs(template) }?
+        .next()
+        .context("Could not find fitting config")?;
+    log::debug!("{config:#?}");
+    let context_attributes = glutin::context::ContextAttributesBuilder::new().build(None);
+    let gl_ctx = unsafe { display.create_context(&config, &context_attributes) }?
+        .make_current_surfaceless()?;
+    Ok((display, gl_ctx))
+}
+
+pub fn run<EH>(options: &Options, once_ready: impl 'static + FnOnce(Rc<Context>) -> EH)
+where
+    EH: 'static + FnMut(Event) -> std::ops::ControlFlow<()>,
+{
+    let context = Rc::new(Context::new(options).expect("Failed to create headless context"));
+    let mut event_handler = once_ready(context.clone());
+    let mut next_frame = std::time::Instant::now();
+    loop {
+        if event_handler(Event::Draw).is_break() {
+            break;
+        }
+        if let Some(fps) = options.headless_fps.or(context.max_fps.get()) {
+            let frame_time = std::time::Duration::from_secs_f64(1.0 / fps);
+            next_frame += frame_time;
+            let now = std::time::Instant::now();
+            if next_frame > now {
+                std::thread::sleep(next_frame - now);
+            } else {
+                // Running behind, dont try to catch up
+                next_frame = now;
+            }
+        }
+    }
+}
+
+impl Context {
+    pub fn new(options: &Options) -> anyhow::Result<Self> {
+        let (display, gl_ctx) = egl::device::Device::query_devices()?
+            .find_map(|device| match create_gl_context(&device) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    log::debug!("Skipping EGL device {device:?}: {e}");
+                    None
+                }
+            })
+            .context("No EGL device supports surfaceless rendering")?;
+        let ugli = Ugli::create_from_glutin(|symbol| {
+            display.get_proc_address(&std::ffi::CString::new(symbol).unwrap())
+        });
+        let size = options.size.unwrap_or(DEFAULT_SIZE);
+        Ok(Self {
+            size,
+            color: RefCell::new(ugli::Texture::new_uninitialized(&ugli, size)),
+            depth: RefCell::new(ugli::Renderbuffer::new(&ugli, size)),
+            ugli,
+            is_fullscreen: Cell::new(false),
+            lock_cursor: Cell::new(false),
+            edited_text: RefCell::new(None),
+            clipboard: RefCell::new(String::new()),
+            vsync: Cell::new(options.vsync),
+            max_fps: Cell::new(None),
+            _gl_ctx: gl_ctx,
+        })
+    }
+
+    pub fn real_size(&self) -> vec2<usize> {
+        self.size
+    }
+
+    pub fn position(&self) -> Option<vec2<i32>> {
+        None
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    // There is no window, so its geometry is fixed
+
+    pub fn set_title(&self, _title: &str) {}
+
+    pub fn set_size(&self, _size: vec2<usize>) {}
+
+    pub fn set_position(&self, _position: vec2<i32>) {}
+
+    pub fn set_min_size(&self, _min_size: Option<vec2<usize>>) {}
+
+    pub fn set_max_size(&self, _max_size: Option<vec2<usize>>) {}
+
+    pub fn set_resizable(&self, _resizable: bool) {}
+
+    pub fn is_resizable(&self) -> bool {
+        false
+    }
+
+    pub fn set_maximized(&self, _maximized: bool) {}
+
+    pub fn is_maximized(&self) -> bool {
+        false
+    }
+
+    pub fn set_minimized(&self, _minimized: bool) {}
+
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        self.is_fullscreen.set(fullscreen);
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.is_fullscreen.get()
+    }
+
+    pub fn set_icon(&self, _path: &std::path::Path) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    pub fn ugli(&self) -> &Ugli {
+        &self.ugli
+    }
+
+    pub fn with_framebuffer<T>(&self, f: impl FnOnce(&mut ugli::Framebuffer) -> T) -> T {
+        let mut color = self.color.borrow_mut();
+        let mut depth = self.depth.borrow_mut();
+        f(&mut ugli::Framebuffer::new(
+            &self.ugli,
+            ugli::ColorAttachment::Texture(&mut color),
+            ugli::DepthAttachment::RenderbufferWithStencil(&mut depth),
+        ))
+    }
+
+    pub fn cursor_locked(&self) -> bool {
+        self.lock_cursor.get()
+    }
+
+    pub fn lock_cursor(&self) {
+        self.lock_cursor.set(true);
+    }
+
+    pub fn unlock_cursor(&self) {
+        self.lock_cursor.set(false);
+    }
+
+    pub fn set_cursor_type(&self, _cursor_type: &CursorType) {}
+
+    pub fn start_text_edit(&self, text: &str) {
+        *self.edited_text.borrow_mut() = Some(text.to_owned());
+    }
+
+    pub fn stop_text_edit(&self) {
+        *self.edited_text.borrow_mut() = None;
+    }
+
+    pub fn is_editing_text(&self) -> bool {
+        self.edited_text.borrow().is_some()
+    }
+
+    pub fn set_text_edit_area(&self, _area: Aabb2<f64>) {}
+
+    pub fn set_clipboard_text(&self, text: &str) {
+        *self.clipboard.borrow_mut() = text.to_owned();
+    }
+
+    pub fn clipboard_text(&self) -> future::LocalBoxFuture<'static, anyhow::Result<String>> {
+        future::ready(Ok(self.clipboard.borrow().clone())).boxed_local()
+    }
+
+    pub fn show(&self) {}
+
+    pub fn set_vsync(&self, vsync: bool) {
+        self.vsync.set(vsync);
+    }
+
+    pub fn vsync(&self) -> bool {
+        self.vsync.get()
+    }
+
+    pub fn set_max_fps(&self, max_fps: Option<f64>) {
+        self.max_fps.set(max_fps);
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.max_fps.get()
+    }
+}
@@ -2,15 +2,100 @@ use super::*;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod glutin_winit;
+#[cfg(not(any(target_arch = "wasm32", target_os = "macos", target_os = "ios")))]
+mod headless;
 #[cfg(target_arch = "wasm32")]
 mod web;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use self::glutin_winit::*;
 #[cfg(target_arch = "wasm32")]
 pub use self::web::*;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use self::glutin_winit::run;
 #[cfg(target_arch = "wasm32")]
 pub use self::web::run;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub enum Context {
+    GlutinWinit(Rc<glutin_winit::Context>),
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    Headless(Rc<headless::Context>),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run<EH>(options: &Options, once_ready: impl 'static + FnOnce(Rc<Context>) -> EH)
+where
+    EH: 'static + FnMut(Event) -> std::ops::ControlFlow<()>,
+{
+    if options.headless {
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        headless::run(options, |context| {
+            once_ready(Rc::new(Context::Headless(context)))
+        });
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        panic!("Headless mode is not supported on this platform");
+    } else {
+        glutin_winit::run(options, |context| {
+            once_ready(Rc::new(Context::GlutinWinit(context)))
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+macro_rules! delegate {
+    ($(fn $name:ident(&self $(, $arg:ident: $arg_ty:ty)*) $(-> $ret:ty)?;)*) => {
+        $(
+            pub fn $name(&self $(, $arg: $arg_ty)*) $(-> $ret)? {
+                match self {
+                    Self::GlutinWinit(context) => context.$name($($arg),*),
+                    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+                    Self::Headless(context) => context.$name($($arg),*),
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Context {
+    delegate! {
+        fn real_size(&self) -> vec2<usize>;
+        fn position(&self) -> Option<vec2<i32>>;
+        fn scale_factor(&self) -> f64;
+        fn set_title(&self, title: &str);
+        fn set_size(&self, size: vec2<usize>);
+        fn set_position(&self, position: vec2<i32>);
+        fn set_min_size(&self, min_size: Option<vec2<usize>>);
+        fn set_max_size(&self, max_size: Option<vec2<usize>>);
+        fn set_resizable(&self, resizable: bool);
+        fn is_resizable(&self) -> bool;
+        fn set_maximized(&self, maximized: bool);
+        fn is_maximized(&self) -> bool;
+        fn set_minimized(&self, minimized: bool);
+        fn set_fullscreen(&self, fullscreen: bool);
+        fn is_fullscreen(&self) -> bool;
+        fn set_icon(&self, path: &std::path::Path) -> anyhow::Result<()>;
+        fn ugli(&self) -> &Ugli;
+        fn cursor_locked(&self) -> bool;
+        fn lock_cursor(&self);
+        fn unlock_cursor(&self);
+        fn set_cursor_type(&self, cursor_type: &CursorType);
+        fn start_text_edit(&self, text: &str);
+        fn stop_text_edit(&self);
+        fn is_editing_text(&self) -> bool;
+        fn set_text_edit_area(&self, area: Aabb2<f64>);
+        fn set_clipboard_text(&self, text: &str);
+        fn clipboard_text(&self) -> future::LocalBoxFuture<'static, anyhow::Result<String>>;
+        fn show(&self);
+        fn set_vsync(&self, vsync: bool);
+        fn vsync(&self) -> bool;
+        fn set_max_fps(&self, max_fps: Option<f64>);
+        fn max_fps(&self) -> Option<f64>;
+    }
+
+    pub fn with_framebuffer<T>(&self, f: impl FnOnce(&mut ugli::Framebuffer) -> T) -> T {
+        match self {
+            Self::GlutinWinit(context) => context.with_framebuffer(f),
+            #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+            Self::Headless(context) => context.with_framebuffer(f),
+        }
+    }
+}
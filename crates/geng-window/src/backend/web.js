@@ -71,6 +71,25 @@ export function set_fullscreen(elem, fullscreen) {
     }
 }
 
+// Clipboard api is missing in insecure contexts (not https) and some browsers
+function clipboard_unavailable() {
+    return Promise.reject(new Error("Clipboard is not available"));
+}
+
+export function set_clipboard_text(text) {
+    if (!navigator.clipboard) {
+        return clipboard_unavailable();
+    }
+    return navigator.clipboard.writeText(text);
+}
+
+export function clipboard_text() {
+    if (!navigator.clipboard) {
+        return clipboard_unavailable();
+    }
+    return navigator.clipboard.readText();
+}
+
 export function show() {
     document.getElementById("geng-progress-screen").style.display = "none";
     document.getElementById("geng-canvas").style.display = "block";
@@ -8,6 +8,8 @@ extern "C" {
     fn is_fullscreen() -> bool;
     fn set_fullscreen(canvas: &web_sys::HtmlCanvasElement, fullscreen: bool);
     fn show();
+    fn set_clipboard_text(text: &str) -> js_sys::Promise;
+    fn clipboard_text() -> js_sys::Promise;
     fn request_animation_frame_loop(closure: &Closure<dyn FnMut()>);
 }
 
@@ -16,6 +18,10 @@ pub struct Context {
     ugli: Ugli,
     editing_text: Rc<Cell<bool>>,
     text_agent: web_sys::HtmlInputElement,
+    /// Text last sent with [Event::EditText], since committed composition
+    /// can be seen both on `compositionend` and on the `input` event after it
+    sent_text: Rc<RefCell<String>>,
+    max_fps: Rc<Cell<Option<f64>>>,
 }
 
 pub fn run<EH>(options: &Options, once_ready: impl 'static + FnOnce(Rc<Context>) -> EH)
@@ -52,6 +58,8 @@ impl Context {
             ugli,
             editing_text: Rc::new(Cell::new(false)),
             text_agent: Self::install_text_agent().unwrap(),
+            sent_text: Rc::new(RefCell::new(String::new())),
+            max_fps: Rc::new(Cell::new(None)),
         }
     }
 
@@ -61,6 +69,46 @@ impl Context {
         vec2(width, height)
     }
 
+    pub fn position(&self) -> Option<vec2<i32>> {
+        None
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        web_sys::window().unwrap().device_pixel_ratio()
+    }
+
+    pub fn set_title(&self, title: &str) {
+        web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .set_title(title);
+    }
+
+    // Canvas geometry is controlled by the page
+
+    pub fn set_size(&self, _size: vec2<usize>) {}
+
+    pub fn set_position(&self, _position: vec2<i32>) {}
+
+    pub fn set_min_size(&self, _min_size: Option<vec2<usize>>) {}
+
+    pub fn set_max_size(&self, _max_size: Option<vec2<usize>>) {}
+
+    pub fn set_resizable(&self, _resizable: bool) {}
+
+    pub fn is_resizable(&self) -> bool {
+        false
+    }
+
+    pub fn set_maximized(&self, _maximized: bool) {}
+
+    pub fn is_maximized(&self) -> bool {
+        false
+    }
+
+    pub fn set_minimized(&self, _minimized: bool) {}
+
     pub fn ugli(&self) -> &Ugli {
         &self.ugli
     }
@@ -132,6 +180,7 @@ impl Context {
     pub fn start_text_edit(&self, text: &str) {
         self.editing_text.set(true);
         self.text_agent.set_value(text);
+        *self.sent_text.borrow_mut() = text.to_owned();
         self.update_text_agent();
     }
 
@@ -144,6 +193,41 @@ impl Context {
         self.editing_text.get()
     }
 
+    /// Moves the hidden input over the area, since the candidate window is shown next to it
+    pub fn set_text_edit_area(&self, area: Aabb2<f64>) {
+        let window = web_sys::window().unwrap();
+        let rect = self.canvas.get_bounding_client_rect();
+        let scale = rect.width() / self.canvas.width().max(1) as f64;
+        let left = window.scroll_x().unwrap_or(0.0) + rect.left() + area.min.x * scale;
+        let top = window.scroll_y().unwrap_or(0.0)
+            + rect.top()
+            + (self.canvas.height() as f64 - area.max.y) * scale;
+        let style = self.text_agent.style();
+        style.set_property("left", &format!("{left}px")).unwrap();
+        style.set_property("top", &format!("{top}px")).unwrap();
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) {
+        let promise = set_clipboard_text(text);
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = wasm_bindgen_futures::JsFuture::from(promise).await {
+                log::error!("Failed to write to clipboard: {e:?}");
+            }
+        });
+    }
+
+    pub fn clipboard_text(&self) -> future::LocalBoxFuture<'static, anyhow::Result<String>> {
+        let promise = clipboard_text();
+        async move {
+            let text = wasm_bindgen_futures::JsFuture::from(promise)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read clipboard: {e:?}"))?;
+            text.as_string()
+                .ok_or_else(|| anyhow::anyhow!("Clipboard contents is not a string"))
+        }
+        .boxed_local()
+    }
+
     pub fn run(
         self: Rc<Self>,
         event_handler: impl FnMut(Event) -> std::ops::ControlFlow<()> + 'static,
@@ -166,6 +250,22 @@ impl Context {
     pub fn show(&self) {
         show();
     }
+
+    pub fn set_vsync(&self, _vsync: bool) {
+        // Browser always synchronizes animation frames with the display
+    }
+
+    pub fn vsync(&self) -> bool {
+        true
+    }
+
+    pub fn set_max_fps(&self, max_fps: Option<f64>) {
+        self.max_fps.set(max_fps);
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.max_fps.get()
+    }
 }
 
 trait Convert<T>: Sized {
@@ -323,15 +423,31 @@ impl Convert<i16> for MouseButton {
 
 impl ConvertEvent<web_sys::KeyboardEvent> for Event {
     fn convert(event: web_sys::KeyboardEvent) -> Vec<Event> {
-        if event.repeat() {
-            return vec![];
-        }
         let Some(key) = Convert::convert(event.code()) else {
             return vec![];
         };
+        let modifiers = Modifiers {
+            shift: event.shift_key(),
+            ctrl: event.ctrl_key(),
+            alt: event.alt_key(),
+            logo: event.meta_key(),
+        };
         vec![match event.type_().as_str() {
-            "keydown" => Event::KeyPress { key },
-            "keyup" => Event::KeyRelease { key },
+            "keydown" => {
+                // Named keys like "Enter" are longer than one character.
+                // Browser does not tell the key without modifiers, so using the one with them
+                let character = Some(event.key()).filter(|key| key.chars().count() == 1);
+                Event::KeyPress {
+                    key,
+                    repeat: event.repeat(),
+                    modifiers,
+                    text: character
+                        .clone()
+                        .filter(|_| !modifiers.ctrl && !modifiers.logo),
+                    logical_key: character,
+                }
+            }
+            "keyup" => Event::KeyRelease { key, modifiers },
             _ => return vec![],
         }]
     }
@@ -472,6 +588,15 @@ impl Context {
         self.subscribe_to_raw(target, handler, event_name);
     }
     fn subscribe_events<F: Fn(Event) + 'static>(&self, handler: F) {
+        /// Send the text unless it was already sent
+        fn send_edit_text(handler: &dyn Fn(Event), sent_text: &RefCell<String>, text: String) {
+            if *sent_text.borrow() == text {
+                return;
+            }
+            *sent_text.borrow_mut() = text.clone();
+            handler(Event::EditText(text));
+        }
+
         let handler = Rc::new(handler);
         let handler = &handler;
 
@@ -483,14 +608,45 @@ impl Context {
             &self.text_agent,
             {
                 let handler = handler.clone();
+                let sent_text = self.sent_text.clone();
                 move |event: web_sys::InputEvent| {
+                    // Composed text is sent when composition ends
+                    if event.is_composing() {
+                        return;
+                    }
                     let input: web_sys::HtmlInputElement =
                         event.target().unwrap().dyn_into().unwrap();
-                    handler(Event::EditText(input.value()));
+                    send_edit_text(&*handler, &sent_text, input.value());
                 }
             },
             "input",
         );
+        for event_name in ["compositionupdate", "compositionend"] {
+            self.subscribe_to_raw::<web_sys::CompositionEvent>(
+                &self.text_agent,
+                {
+                    let handler = handler.clone();
+                    let sent_text = self.sent_text.clone();
+                    move |event: web_sys::CompositionEvent| {
+                        if event.type_() == "compositionend" {
+                            handler(Event::TextComposition {
+                                text: String::new(),
+                                cursor_range: None,
+                            });
+                            let input: web_sys::HtmlInputElement =
+                                event.target().unwrap().dyn_into().unwrap();
+                            send_edit_text(&*handler, &sent_text, input.value());
+                        } else {
+                            handler(Event::TextComposition {
+                                text: event.data().unwrap_or_default(),
+                                cursor_range: None,
+                            });
+                        }
+                    }
+                },
+                event_name,
+            );
+        }
         self.subscribe_to::<web_sys::MouseEvent>(&self.canvas, handler, "mousedown");
         self.subscribe_to::<web_sys::MouseEvent>(&self.canvas, handler, "mouseup");
         self.subscribe_to::<web_sys::MouseEvent>(&self.canvas, handler, "mousemove");
@@ -502,10 +658,36 @@ impl Context {
         self.subscribe_to::<web_sys::MouseEvent>(&self.canvas, handler, "contextmenu");
         {
             let handler = handler.clone();
-            let closure =
-                wasm_bindgen::closure::Closure::wrap(
-                    Box::new(move || handler(Event::Draw)) as Box<dyn FnMut()>
-                );
+            let max_fps = self.max_fps.clone();
+            let canvas = self.canvas.clone();
+            let mut last_size = None;
+            let mut last_scale_factor = None;
+            let mut last_frame = js_sys::Date::now();
+            let closure = wasm_bindgen::closure::Closure::wrap(Box::new(move || {
+                // Canvas is resized by the page and browser zoom changes pixel ratio,
+                // so checking every frame
+                let scale_factor = web_sys::window().unwrap().device_pixel_ratio();
+                if last_scale_factor
+                    .replace(scale_factor)
+                    .is_some_and(|last| last != scale_factor)
+                {
+                    handler(Event::ScaleFactorChanged(scale_factor));
+                }
+                let size = vec2(canvas.width(), canvas.height()).map(|x| x as usize);
+                if last_size.replace(size).is_some_and(|last| last != size) {
+                    handler(Event::Resized(size));
+                }
+                let now = js_sys::Date::now();
+                if let Some(max_fps) = max_fps.get() {
+                    // Skipping animation frames until enough time passed,
+                    // with a millisecond of tolerance since frame times jitter
+                    if now - last_frame < 1000.0 / max_fps - 1.0 {
+                        return;
+                    }
+                }
+                last_frame = now;
+                handler(Event::Draw);
+            }) as Box<dyn FnMut()>);
             request_animation_frame_loop(&closure);
             std::mem::forget(closure); // Don't drop so that JS can call this thing
         };
@@ -18,6 +18,28 @@ pub enum MouseButton {
     Right,
 }
 
+/// Modifier keys held during a key event
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Windows key, or Command on macOS
+    pub logo: bool,
+}
+
+impl Modifiers {
+    /// Whether the modifier used for shortcuts like copy and paste is held,
+    /// which is Command on macOS and Ctrl elsewhere
+    pub fn command(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Touch {
     pub id: u64,
@@ -27,20 +49,75 @@ pub struct Touch {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Event {
-    MousePress { button: MouseButton },
-    MouseRelease { button: MouseButton },
-    CursorMove { position: vec2<f64> },
-    RawMouseMove { delta: vec2<f64> },
-    Wheel { delta: f64 },
+    MousePress {
+        button: MouseButton,
+    },
+    MouseRelease {
+        button: MouseButton,
+    },
+    CursorMove {
+        position: vec2<f64>,
+    },
+    RawMouseMove {
+        delta: vec2<f64>,
+    },
+    Wheel {
+        delta: f64,
+    },
     TouchStart(Touch),
     TouchMove(Touch),
     TouchEnd(Touch),
-    KeyPress { key: Key },
-    KeyRelease { key: Key },
+    KeyPress {
+        /// Physical key, same for any keyboard layout
+        key: Key,
+        /// Whether the key is being held and the press is repeated by the system.
+        ///
+        /// Repeated presses are sent too, so match `repeat: false`
+        /// for actions that should only happen once per press, like toggles
+        repeat: bool,
+        modifiers: Modifiers,
+        /// Key in the current keyboard layout ignoring modifiers,
+        /// like `"z"` for [Key::W] on AZERTY layout, or `None` if it is not a character key
+        logical_key: Option<String>,
+        /// Text produced by the press, with modifiers applied
+        text: Option<String>,
+    },
+    KeyRelease {
+        key: Key,
+        modifiers: Modifiers,
+    },
     EditText(String),
+    /// Text being composed with an input method while editing text, not yet part of [Event::EditText].
+    ///
+    /// Empty text means composition has ended.
+    /// Cursor range is in bytes of the composed text, if known
+    TextComposition {
+        text: String,
+        cursor_range: Option<std::ops::Range<usize>>,
+    },
     Draw,
     CloseRequested,
     Focused(bool),
+    /// Window was resized, with the new size in physical pixels
+    Resized(vec2<usize>),
+    /// Window was moved, with the new position on the screen in physical pixels
+    Moved(vec2<i32>),
+    /// Window was moved to a display with a different pixel density, or the density changed
+    ScaleFactorChanged(f64),
+    /// Window became fully hidden by other windows or minimized (`true`), or visible again
+    Occluded(bool),
+    /// File is being dragged over the window, sent for each file if multiple
+    FileHovered {
+        path: std::path::PathBuf,
+        position: vec2<f64>,
+    },
+    /// Files that were dragged over the window left it without being dropped
+    FileHoverCancelled,
+    /// File was dropped onto the window, sent for each file if multiple
+    FileDropped {
+        path: std::path::PathBuf,
+        position: vec2<f64>,
+    },
 }
 
 impl Event {
@@ -50,6 +127,12 @@ impl Event {
         match result {
             CursorMove {
                 ref mut position, ..
+            }
+            | FileHovered {
+                ref mut position, ..
+            }
+            | FileDropped {
+                ref mut position, ..
             } => *position += delta,
             TouchStart(ref mut touch) | TouchMove(ref mut touch) | TouchEnd(ref mut touch) => {
                 touch.position += delta;
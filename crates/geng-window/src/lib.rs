@@ -10,6 +10,7 @@ mod backend;
 
 mod cursor;
 mod events;
+mod wait;
 
 pub use cursor::*;
 pub use events::*;
@@ -32,6 +33,12 @@ pub struct CliArgs {
     /// Start in fullscreen
     #[clap(long, value_name = "BOOL")]
     pub fullscreen: Option<bool>,
+    /// Run without a window, rendering offscreen
+    #[clap(long, value_name = "BOOL")]
+    pub headless: Option<bool>,
+    /// Rate of draw events when running headless (as fast as possible by default)
+    #[clap(long, value_name = "FPS")]
+    pub headless_fps: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -43,8 +50,20 @@ pub struct Options {
     pub transparency: bool,
     pub mouse_passthrough: bool,
     pub size: Option<vec2<usize>>,
+    /// Initial position of the window on the screen, in physical pixels
+    pub position: Option<vec2<i32>>,
+    /// Minimum size the window can be resized to, in physical pixels
+    pub min_size: Option<vec2<usize>>,
+    /// Maximum size the window can be resized to, in physical pixels
+    pub max_size: Option<vec2<usize>>,
+    pub resizable: bool,
+    pub maximized: bool,
     pub auto_close: bool,
     pub start_hidden: bool,
+    /// Render offscreen without creating a window
+    pub headless: bool,
+    /// Rate of [Event::Draw] when running headless, `None` means as fast as possible
+    pub headless_fps: Option<f64>,
 }
 
 impl Options {
@@ -57,8 +76,15 @@ impl Options {
             transparency: false,
             mouse_passthrough: false,
             size: None,
+            position: None,
+            min_size: None,
+            max_size: None,
+            resizable: true,
+            maximized: false,
             auto_close: true,
             start_hidden: false,
+            headless: false,
+            headless_fps: None,
         }
     }
 
@@ -75,6 +101,12 @@ impl Options {
         if let Some(fullscreen) = args.fullscreen {
             self.fullscreen = fullscreen;
         }
+        if let Some(headless) = args.headless {
+            self.headless = headless;
+        }
+        if let Some(headless_fps) = args.headless_fps {
+            self.headless_fps = Some(headless_fps);
+        }
     }
 }
 
@@ -89,6 +121,10 @@ struct WindowImpl {
     cursor_type: RefCell<CursorType>,
     auto_close: Cell<bool>,
     current_event: RefCell<Option<Event>>,
+    frame_timer: RefCell<batbox_time::Timer>,
+    delta_time: Cell<f64>,
+    #[allow(clippy::type_complexity)]
+    event_hook: RefCell<Option<Box<dyn FnMut(Event) -> Vec<Event>>>>,
 }
 
 #[derive(Clone)]
@@ -109,13 +145,79 @@ impl Window {
         self.inner.backend.is_editing_text()
     }
 
+    /// Set area of the edited text in window coordinates (same as cursor position),
+    /// so that the input method candidate window is shown next to it
+    pub fn set_text_edit_area(&self, area: Aabb2<f64>) {
+        self.inner.backend.set_text_edit_area(area);
+    }
+
+    /// Size of the window in physical pixels
     pub fn real_size(&self) -> vec2<usize> {
         self.inner.backend.real_size()
     }
+    /// Number of physical pixels per logical pixel, as configured in the OS.
+    ///
+    /// [Event::ScaleFactorChanged] is sent when it changes
+    pub fn scale_factor(&self) -> f64 {
+        self.inner.backend.scale_factor()
+    }
+    /// Size of the window in logical pixels, see [Window::scale_factor]
+    pub fn logical_size(&self) -> vec2<f64> {
+        self.size().map(|x| x as f64 / self.scale_factor())
+    }
+    /// Position of the window on the screen, if supported by the platform
+    pub fn position(&self) -> Option<vec2<i32>> {
+        self.inner.backend.position()
+    }
+    /// Size of the window in physical pixels, at least 1x1
     pub fn size(&self) -> vec2<usize> {
         self.real_size().map(|x| x.max(1))
     }
 
+    pub fn set_title(&self, title: &str) {
+        self.inner.backend.set_title(title);
+    }
+
+    /// Request the window to be resized, in physical pixels.
+    ///
+    /// The size may be changed by the system, [Event::Resized] is sent when actually resized
+    pub fn set_size(&self, size: vec2<usize>) {
+        self.inner.backend.set_size(size);
+    }
+
+    /// Move the window on the screen, if supported by the platform
+    pub fn set_position(&self, position: vec2<i32>) {
+        self.inner.backend.set_position(position);
+    }
+
+    pub fn set_min_size(&self, min_size: Option<vec2<usize>>) {
+        self.inner.backend.set_min_size(min_size);
+    }
+
+    pub fn set_max_size(&self, max_size: Option<vec2<usize>>) {
+        self.inner.backend.set_max_size(max_size);
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        self.inner.backend.set_resizable(resizable);
+    }
+
+    pub fn is_resizable(&self) -> bool {
+        self.inner.backend.is_resizable()
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        self.inner.backend.set_maximized(maximized);
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.inner.backend.is_maximized()
+    }
+
+    pub fn set_minimized(&self, minimized: bool) {
+        self.inner.backend.set_minimized(minimized);
+    }
+
     pub fn ugli(&self) -> &Ugli {
         self.inner.backend.ugli()
     }
@@ -140,6 +242,19 @@ impl Window {
         self.inner.backend.set_fullscreen(fullscreen);
     }
 
+    /// Copy text into the system clipboard
+    pub fn set_clipboard_text(&self, text: &str) {
+        self.inner.backend.set_clipboard_text(text);
+    }
+
+    /// Read text from the system clipboard.
+    ///
+    /// On the web this may ask the user for permission,
+    /// and only works in response to user input in some browsers
+    pub async fn clipboard_text(&self) -> anyhow::Result<String> {
+        self.inner.backend.clipboard_text().await
+    }
+
     pub fn is_fullscreen(&self) -> bool {
         self.inner.backend.is_fullscreen()
     }
@@ -180,6 +295,24 @@ impl Window {
         self.inner.backend.show();
     }
 
+    /// Turn vertical synchronization on/off (always on for the web)
+    pub fn set_vsync(&self, vsync: bool) {
+        self.inner.backend.set_vsync(vsync);
+    }
+
+    pub fn vsync(&self) -> bool {
+        self.inner.backend.vsync()
+    }
+
+    /// Limit the rate of [Event::Draw], `None` means no limit
+    pub fn set_max_fps(&self, max_fps: Option<f64>) {
+        self.inner.backend.set_max_fps(max_fps);
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.inner.backend.max_fps()
+    }
+
     pub async fn yield_now(&self) {
         self.events()
             .filter(|event| future::ready(matches!(event, Event::Draw)))
@@ -187,9 +320,83 @@ impl Window {
             .await;
     }
 
+    /// Time in seconds between the last two [Event::Draw]s
+    pub fn delta_time(&self) -> f64 {
+        self.inner.delta_time.get()
+    }
+
     pub fn current_event(&self) -> Option<Event> {
         self.inner.current_event.borrow().clone()
     }
+
+    /// Set a hook that replaces every event coming from the backend with a list of events.
+    ///
+    /// Can be used to record or replay input.
+    pub fn set_event_hook(&self, hook: Option<Box<dyn FnMut(Event) -> Vec<Event>>>) {
+        *self.inner.event_hook.borrow_mut() = hook;
+    }
+
+    fn handle_event(
+        &self,
+        event: Event,
+        main_task: &async_executor::Task<()>,
+    ) -> std::ops::ControlFlow<()> {
+        let mut close = false;
+        match event {
+            Event::Draw => {
+                let delta_time = self.inner.frame_timer.borrow_mut().tick();
+                self.inner.delta_time.set(delta_time.as_secs_f64());
+            }
+            Event::KeyPress { key, repeat, .. } => {
+                if !self.inner.pressed_keys.borrow_mut().insert(key) && !repeat {
+                    return std::ops::ControlFlow::Continue(());
+                }
+            }
+            Event::KeyRelease { key, .. } => {
+                if !self.inner.pressed_keys.borrow_mut().remove(&key) {
+                    return std::ops::ControlFlow::Continue(());
+                }
+            }
+            Event::MousePress { button } => {
+                self.inner.pressed_buttons.borrow_mut().insert(button);
+            }
+            Event::MouseRelease { button } => {
+                self.inner.pressed_buttons.borrow_mut().remove(&button);
+            }
+            Event::CursorMove { position } => {
+                self.inner.cursor_pos.set(Some(position));
+                if self.cursor_locked() {
+                    return std::ops::ControlFlow::Continue(());
+                }
+            }
+            Event::RawMouseMove { .. } => {
+                if !self.cursor_locked() {
+                    return std::ops::ControlFlow::Continue(());
+                }
+            }
+            Event::CloseRequested => {
+                // Still deliver the event so that tasks get a chance to clean up before exit
+                close = self.is_auto_close();
+            }
+            _ => {}
+        }
+        self.inner.current_event.borrow_mut().replace(event.clone());
+        if let Some(_removed) = self.inner.event_sender.try_broadcast(event).unwrap() {
+            // log::error!("Event has been ignored: {removed:?}");
+        }
+        self.inner.event_receiver.borrow_mut().try_recv().unwrap();
+        while self.inner.executor.try_tick() {
+            if main_task.is_finished() {
+                return std::ops::ControlFlow::Break(());
+            }
+        }
+        self.inner.current_event.borrow_mut().take();
+        // Main task could have finished before any events were handled
+        if close || main_task.is_finished() {
+            return std::ops::ControlFlow::Break(());
+        }
+        std::ops::ControlFlow::Continue(())
+    }
 }
 
 pub fn run<Fut>(options: &Options, f: impl 'static + FnOnce(Window) -> Fut)
@@ -214,6 +421,9 @@ where
                 cursor_pos: Cell::new(None),
                 cursor_type: RefCell::new(CursorType::Default),
                 current_event: RefCell::new(None),
+                frame_timer: RefCell::new(batbox_time::Timer::new()),
+                delta_time: Cell::new(0.0),
+                event_hook: RefCell::new(None),
             }),
         };
         #[cfg(not(target_arch = "wasm32"))]
@@ -228,56 +438,15 @@ where
         let main_task = window.spawn(f);
         while window.inner.executor.try_tick() {}
         move |event| {
-            match event {
-                Event::KeyPress { key } => {
-                    if !window.inner.pressed_keys.borrow_mut().insert(key) {
-                        return std::ops::ControlFlow::Continue(());
-                    }
-                }
-                Event::KeyRelease { key } => {
-                    if !window.inner.pressed_keys.borrow_mut().remove(&key) {
-                        return std::ops::ControlFlow::Continue(());
-                    }
-                }
-                Event::MousePress { button } => {
-                    window.inner.pressed_buttons.borrow_mut().insert(button);
-                }
-                Event::MouseRelease { button } => {
-                    window.inner.pressed_buttons.borrow_mut().remove(&button);
-                }
-                Event::CursorMove { position } => {
-                    window.inner.cursor_pos.set(Some(position));
-                    if window.cursor_locked() {
-                        return std::ops::ControlFlow::Continue(());
-                    }
-                }
-                Event::RawMouseMove { .. } => {
-                    if !window.cursor_locked() {
-                        return std::ops::ControlFlow::Continue(());
-                    }
-                }
-                Event::CloseRequested => {
-                    if window.is_auto_close() {
-                        return std::ops::ControlFlow::Break(());
-                    }
-                }
-                _ => {}
-            }
-            window
-                .inner
-                .current_event
-                .borrow_mut()
-                .replace(event.clone());
-            if let Some(_removed) = window.inner.event_sender.try_broadcast(event).unwrap() {
-                // log::error!("Event has been ignored: {removed:?}");
-            }
-            window.inner.event_receiver.borrow_mut().try_recv().unwrap();
-            while window.inner.executor.try_tick() {
-                if main_task.is_finished() {
+            let events = match &mut *window.inner.event_hook.borrow_mut() {
+                Some(hook) => hook(event),
+                None => vec![event],
+            };
+            for event in events {
+                if window.handle_event(event, &main_task).is_break() {
                     return std::ops::ControlFlow::Break(());
                 }
             }
-            window.inner.current_event.borrow_mut().take();
             std::ops::ControlFlow::Continue(())
         }
     });
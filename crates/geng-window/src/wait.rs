@@ -0,0 +1,55 @@
+use super::*;
+
+use batbox_time::Duration;
+
+/// Async helpers for scripted sequences like cutscenes, driven by [Event::Draw]
+impl Window {
+    /// Wait until given duration has passed, counted with [Window::delta_time] of every frame
+    pub async fn sleep(&self, duration: Duration) {
+        let mut elapsed = 0.0;
+        while elapsed < duration.as_secs_f64() {
+            self.yield_now().await;
+            elapsed += self.delta_time();
+        }
+    }
+
+    /// Wait for the next press of the key, ignoring repeats if it was already held
+    pub async fn wait_for_key(&self, key: Key) {
+        self.events()
+            .filter(|event| {
+                future::ready(matches!(
+                    event,
+                    Event::KeyPress { key: pressed, repeat: false, .. } if *pressed == key
+                ))
+            })
+            .next()
+            .await;
+    }
+
+    /// Wait for the next mouse button press or touch
+    pub async fn wait_for_click(&self) {
+        self.events()
+            .filter(|event| {
+                future::ready(matches!(
+                    event,
+                    Event::MousePress { .. } | Event::TouchStart(_)
+                ))
+            })
+            .next()
+            .await;
+    }
+
+    /// Run the future until it completes or the duration passes, in which case `None` is returned
+    pub async fn timeout<T>(
+        &self,
+        future: impl Future<Output = T>,
+        duration: Duration,
+    ) -> Option<T> {
+        let future = std::pin::pin!(future);
+        let sleep = std::pin::pin!(self.sleep(duration));
+        match future::select(future, sleep).await {
+            future::Either::Left((value, _)) => Some(value),
+            future::Either::Right(((), _)) => None,
+        }
+    }
+}
@@ -52,15 +52,19 @@ impl geng::State for State {
         );
     }
     // We can handle events like KeyDown by implementing this method
-    fn handle_event(&mut self, event: geng::Event) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
         if matches!(
             event,
             geng::Event::KeyPress {
-                key: geng::Key::Space
+                key: geng::Key::Space,
+                repeat: false,
+                ..
             }
         ) {
             self.position = vec2::ZERO;
+            return true;
         }
+        false
     }
 }
 
@@ -54,10 +54,12 @@ impl geng::State for State {
             Rgba::WHITE,
         );
     }
-    fn handle_event(&mut self, event: geng::Event) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
         match event {
             geng::Event::KeyPress {
                 key: geng::Key::Space,
+                repeat: false,
+                ..
             } => {
                 *self = Self::new(&self.geng);
             }
@@ -147,8 +149,9 @@ impl geng::State for State {
                 self.touches.retain(|t| t.id != touch.id);
                 self.drag_start = None;
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 }
 
@@ -50,9 +50,15 @@ impl geng::State for CrabRave {
             // self.legs[i] += (p - self.legs[i]) * (delta_time as f32 * 10.0).min(1.0);
         }
     }
-    fn handle_event(&mut self, event: geng::Event) {
-        if let geng::Event::KeyPress { key: geng::Key::S } = event {
-            if self.geng.window().is_key_pressed(geng::Key::ControlLeft) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
+        if let geng::Event::KeyPress {
+            key: geng::Key::S,
+            repeat: false,
+            modifiers,
+            ..
+        } = event
+        {
+            if modifiers.command() {
                 let mut texture =
                     ugli::Texture::new_uninitialized(self.geng.ugli(), self.geng.window().size());
                 texture.render_into(|framebuffer| {
@@ -64,8 +70,10 @@ impl geng::State for CrabRave {
                     .write_to(&mut output, image::ImageFormat::Png)
                     .unwrap();
                 file_dialog::save("crabrave.png", &output.into_inner()).unwrap();
+                return true;
             }
         }
+        false
     }
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
         ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
@@ -0,0 +1,54 @@
+use geng::prelude::*;
+
+fn main() {
+    logger::init();
+    geng::setup_panic_handler();
+    Geng::run("Drop an image", |geng| async move {
+        let mut texture: Option<ugli::Texture> = None;
+        let mut hovered = false;
+        let mut events = geng.window().events();
+        while let Some(event) = events.next().await {
+            match event {
+                geng::Event::FileHovered { .. } => hovered = true,
+                geng::Event::FileHoverCancelled => hovered = false,
+                geng::Event::FileDropped { path, .. } => {
+                    hovered = false;
+                    match geng.asset_manager().load::<ugli::Texture>(&path).await {
+                        Ok(loaded) => texture = Some(loaded),
+                        Err(e) => log::error!("Failed to load {path:?}: {e}"),
+                    }
+                }
+                geng::Event::Draw => {
+                    geng.window().with_framebuffer(|framebuffer| {
+                        let background = if hovered {
+                            Rgba::new(0.2, 0.2, 0.2, 1.0)
+                        } else {
+                            Rgba::BLACK
+                        };
+                        ugli::clear(framebuffer, Some(background), None, None);
+                        let center = framebuffer.size().map(|x| x as f32) / 2.0;
+                        match &texture {
+                            Some(texture) => geng.draw2d().textured_quad(
+                                framebuffer,
+                                &geng::PixelPerfectCamera,
+                                Aabb2::point(center)
+                                    .extend_symmetric(texture.size().map(|x| x as f32) / 2.0),
+                                texture,
+                                Rgba::WHITE,
+                            ),
+                            None => geng.default_font().draw(
+                                framebuffer,
+                                &geng::PixelPerfectCamera,
+                                "Drop an image here",
+                                vec2::splat(geng::TextAlign::CENTER),
+                                mat3::translate(center) * mat3::scale_uniform(32.0),
+                                Rgba::WHITE,
+                            ),
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+    });
+}
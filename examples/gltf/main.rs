@@ -195,7 +195,7 @@ impl geng::State for Example {
             );
         }
     }
-    fn handle_event(&mut self, event: geng::Event) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
         match event {
             geng::Event::MousePress { .. } => {
                 self.geng.window().lock_cursor();
@@ -211,14 +211,20 @@ impl geng::State for Example {
                 .clamp_range(Angle::ZERO..=Angle::from_radians(f32::PI));
             }
 
-            geng::Event::KeyPress { key: geng::Key::S }
-                if self.geng.window().is_key_pressed(geng::Key::ControlLeft) =>
-            {
+            geng::Event::KeyPress {
+                key: geng::Key::S,
+                repeat: false,
+                modifiers,
+                ..
+            } if modifiers.command() => {
                 file_dialog::save("test.txt", "Hello, world!".as_bytes()).unwrap();
             }
-            geng::Event::KeyPress { key: geng::Key::O }
-                if self.geng.window().is_key_pressed(geng::Key::ControlLeft) =>
-            {
+            geng::Event::KeyPress {
+                key: geng::Key::O,
+                repeat: false,
+                modifiers,
+                ..
+            } if modifiers.command() => {
                 let geng = self.geng.clone();
                 let assets = self.assets.clone();
                 let transition = self.transition.clone();
@@ -233,8 +239,9 @@ impl geng::State for Example {
                     )))
                 });
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
     fn transition(&mut self) -> Option<geng::state::Transition> {
         self.transition.borrow_mut().take()
@@ -42,10 +42,16 @@ fn main() {
                 }
                 geng::Event::KeyPress {
                     key: geng::Key::Space,
+                    repeat: false,
+                    ..
                 } => {
                     hello.play();
                 }
-                geng::Event::KeyPress { key: geng::Key::M } => {
+                geng::Event::KeyPress {
+                    key: geng::Key::M,
+                    repeat: false,
+                    ..
+                } => {
                     let mut effect = music.effect(geng.audio().default_type());
                     effect.set_looped(true);
                     effect.set_speed(1.1);
@@ -53,7 +59,9 @@ fn main() {
                     effect.play();
                     music_effect = Some(effect);
                 }
-                geng::Event::KeyRelease { key: geng::Key::M } => {
+                geng::Event::KeyRelease {
+                    key: geng::Key::M, ..
+                } => {
                     if let Some(mut effect) = music_effect.take() {
                         effect.fade_out(fade_duration);
                     }
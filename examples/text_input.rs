@@ -3,6 +3,8 @@ use geng::prelude::*;
 struct State {
     geng: Geng,
     text: String,
+    /// Text being composed with an input method, shown after the edited text
+    composition: String,
 }
 
 impl State {
@@ -10,6 +12,7 @@ impl State {
         Self {
             geng: geng.clone(),
             text: "Click to start editing".to_owned(),
+            composition: String::new(),
         }
     }
 }
@@ -24,27 +27,35 @@ impl geng::State for State {
                 rotation: Angle::ZERO,
                 fov: Camera2dFov::Vertical(15.0),
             },
-            &self.text,
+            &format!("{}{}", self.text, self.composition),
             vec2::splat(geng::TextAlign::CENTER),
             mat3::identity(),
             Rgba::WHITE,
         );
     }
-    fn handle_event(&mut self, event: geng::Event) {
+    fn handle_event(&mut self, event: geng::Event) -> bool {
         match event {
             geng::Event::TouchStart(_) | geng::Event::MousePress { .. } => {
-                self.geng.window().start_text_edit(&self.text);
+                let window = self.geng.window();
+                window.start_text_edit(&self.text);
+                let center = window.size().map(|x| x as f64) / 2.0;
+                window.set_text_edit_area(Aabb2::point(center).extend_uniform(20.0));
             }
             geng::Event::EditText(text) => {
                 self.text = text;
             }
+            geng::Event::TextComposition { text, .. } => {
+                self.composition = text;
+            }
             geng::Event::KeyPress {
                 key: geng::Key::Backspace,
+                ..
             } => {
                 self.text.pop();
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 }
 
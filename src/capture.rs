@@ -0,0 +1,152 @@
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum CaptureFormat {
+    /// Numbered png image per frame
+    Png,
+    /// Single animated gif, encoded when capture is stopped
+    Gif,
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    /// Directory to write captured frames into
+    pub path: std::path::PathBuf,
+    pub format: CaptureFormat,
+    /// Index of the first frame to capture
+    pub start_frame: usize,
+    /// Index of the frame to stop capturing at, `None` means capture until stopped
+    pub end_frame: Option<usize>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            path: "capture".into(),
+            format: CaptureFormat::Png,
+            start_frame: 0,
+            end_frame: None,
+        }
+    }
+}
+
+pub(crate) struct Capture {
+    options: CaptureOptions,
+    gif_frames: Vec<image::Frame>,
+    // Gif frame delay is only known when the next frame arrives
+    pending_gif_frame: Option<image::RgbaImage>,
+    last_delay: image::Delay,
+    timer: Timer,
+}
+
+impl Capture {
+    fn frame(&mut self, index: usize, image: image::RgbaImage) -> anyhow::Result<()> {
+        match self.options.format {
+            CaptureFormat::Png => {
+                image.save(self.options.path.join(format!("frame-{index:06}.png")))?;
+            }
+            CaptureFormat::Gif => {
+                let delay = std::time::Duration::from_secs_f64(self.timer.tick().as_secs_f64());
+                if let Some(previous) = self.pending_gif_frame.replace(image) {
+                    self.last_delay = image::Delay::from_saturating_duration(delay);
+                    self.gif_frames
+                        .push(image::Frame::from_parts(previous, 0, 0, self.last_delay));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> anyhow::Result<()> {
+        if let CaptureFormat::Gif = self.options.format {
+            if let Some(last) = self.pending_gif_frame.take() {
+                self.gif_frames
+                    .push(image::Frame::from_parts(last, 0, 0, self.last_delay));
+            }
+            let path = self
+                .options
+                .path
+                .join(format!("capture-{:06}.gif", self.options.start_frame));
+            log::info!("Encoding {} frames into {path:?}", self.gif_frames.len());
+            let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            let mut encoder = image::codecs::gif::GifEncoder::new(file);
+            encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?;
+            encoder.encode_frames(self.gif_frames)?;
+        }
+        Ok(())
+    }
+}
+
+impl Geng {
+    /// Start capturing frames drawn by [Geng::run_state].
+    ///
+    /// If a capture is already running, it is stopped first.
+    pub fn start_capture(&self, options: CaptureOptions) -> anyhow::Result<()> {
+        self.stop_capture();
+        std::fs::create_dir_all(&options.path)?;
+        log::info!("Capturing frames into {:?}", options.path);
+        *self.inner.capture.borrow_mut() = Some(Capture {
+            options,
+            gif_frames: Vec::new(),
+            pending_gif_frame: None,
+            last_delay: image::Delay::from_numer_denom_ms(1000, 60),
+            timer: Timer::new(),
+        });
+        Ok(())
+    }
+
+    /// Stop capturing frames, writing the gif if capturing in that format
+    pub fn stop_capture(&self) {
+        let Some(capture) = self.inner.capture.borrow_mut().take() else {
+            return;
+        };
+        if let Err(e) = capture.finish() {
+            log::error!("Failed to finish capture: {e}");
+        }
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.inner.capture.borrow().is_some()
+    }
+
+    /// Start capturing from the current frame if not capturing, stop otherwise
+    pub fn toggle_capture(&self) {
+        if self.is_capturing() {
+            self.stop_capture();
+            return;
+        }
+        let options = CaptureOptions {
+            start_frame: self.inner.frame_index.get(),
+            end_frame: None,
+            ..self.inner.options.capture.clone().unwrap_or_default()
+        };
+        if let Err(e) = self.start_capture(options) {
+            log::error!("Failed to start capture: {e}");
+        }
+    }
+
+    pub(crate) fn capture_frame(&self, framebuffer: &ugli::Framebuffer) {
+        let index = self.inner.frame_index.get();
+        let mut capture = self.inner.capture.borrow_mut();
+        let Some(current) = &mut *capture else {
+            return;
+        };
+        if current
+            .options
+            .end_frame
+            .is_some_and(|end_frame| index >= end_frame)
+        {
+            std::mem::drop(capture);
+            self.stop_capture();
+            return;
+        }
+        if index < current.options.start_frame {
+            return;
+        }
+        let image = framebuffer.read_color().into_image_image();
+        if let Err(e) = current.frame(index, image) {
+            log::error!("Failed to capture frame: {e}");
+            capture.take();
+        }
+    }
+}
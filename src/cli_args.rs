@@ -8,6 +8,36 @@ pub struct CliArgs {
     /// Enable/disable hot reloading of assets
     #[clap(long, value_name = "BOOL")]
     pub hot_reload: Option<bool>,
+    /// Limit the frame rate
+    #[clap(long, value_name = "FPS")]
+    pub max_fps: Option<f64>,
+    /// Enable profiler and show its panel
+    #[clap(long, value_name = "BOOL")]
+    pub profiler: Option<bool>,
+    /// Enable/disable the debug overlay
+    #[clap(long, value_name = "BOOL")]
+    pub debug_overlay: Option<bool>,
+    /// Language to use instead of the system one
+    #[clap(long, value_name = "LANG")]
+    pub language: Option<String>,
+    /// Record input into a file
+    #[clap(long, value_name = "FILE")]
+    pub record_input: Option<std::path::PathBuf>,
+    /// Replay input from a file recorded with --record-input
+    #[clap(long, value_name = "FILE")]
+    pub replay_input: Option<std::path::PathBuf>,
+    /// Capture drawn frames into a directory
+    #[clap(long, value_name = "DIR")]
+    pub capture_frames: Option<std::path::PathBuf>,
+    /// Format of captured frames
+    #[clap(long, value_name = "FORMAT")]
+    pub capture_format: Option<CaptureFormat>,
+    /// Index of the first frame to capture
+    #[clap(long, value_name = "FRAME")]
+    pub capture_start: Option<usize>,
+    /// Index of the frame to stop capturing at
+    #[clap(long, value_name = "FRAME")]
+    pub capture_end: Option<usize>,
 }
 
 impl ContextOptions {
@@ -16,5 +46,41 @@ impl ContextOptions {
         if let Some(hot_reload) = args.hot_reload {
             self.hot_reload = hot_reload;
         }
+        if let Some(max_fps) = args.max_fps {
+            self.max_fps = Some(max_fps);
+        }
+        if let Some(profiler) = args.profiler {
+            self.profiler = profiler;
+        }
+        if let Some(debug_overlay) = args.debug_overlay {
+            self.debug_overlay = debug_overlay;
+        }
+        if let Some(language) = &args.language {
+            self.language = Some(language.clone());
+        }
+        if let Some(path) = &args.record_input {
+            self.record_input = Some(path.clone());
+        }
+        if let Some(path) = &args.replay_input {
+            self.replay_input = Some(path.clone());
+        }
+        if let Some(path) = &args.capture_frames {
+            self.capture = Some(CaptureOptions {
+                path: path.clone(),
+                ..default()
+            });
+        }
+        if let Some(capture) = &mut self.capture {
+            if let Some(format) = args.capture_format {
+                capture.format = format;
+            }
+            if let Some(start_frame) = args.capture_start {
+                capture.start_frame = start_frame;
+            }
+            if let Some(end_frame) = args.capture_end {
+                capture.end_frame = Some(end_frame);
+            }
+        }
+        self.cli = Some(args.clone());
     }
 }
@@ -8,11 +8,26 @@ pub(crate) struct GengImpl {
     pub(crate) draw2d: Rc<draw2d::Helper>,
     asset_manager: asset::Manager,
     default_font: Rc<Font>,
-    fixed_delta_time: Cell<f64>,
+    pub(crate) fixed_delta_time: Cell<f64>,
     max_delta_time: Cell<f64>,
+    max_fixed_updates_per_frame: Cell<usize>,
+    pub(crate) fixed_update_alpha: Cell<f64>,
+    dropped_time: Cell<f64>,
     ui_theme: RefCell<Option<ui::Theme>>,
     pub(crate) options: ContextOptions,
-    pub(crate) load_progress: RefCell<asset::LoadProgress>,
+    pub(crate) loading_progress: RefCell<LoadingProgress>,
+    pub(crate) clock: RefCell<Box<dyn Clock>>,
+    pub(crate) input_session: RefCell<Option<replay::Session>>,
+    pub(crate) capture: RefCell<Option<Capture>>,
+    pub(crate) frame_index: Cell<usize>,
+    /// Overlays not yet picked up by [Geng::run_state]
+    #[allow(clippy::type_complexity)]
+    pub(crate) new_overlays: RefCell<Vec<(OverlayPosition, Box<dyn Overlay>)>>,
+    pub(crate) settings: RefCell<settings::Settings>,
+    pub(crate) localization: RefCell<Option<Rc<dyn Translate>>>,
+    pub(crate) language: RefCell<String>,
+    #[cfg(feature = "audio")]
+    pub(crate) sound_types: RefCell<std::collections::HashMap<String, SoundType>>,
     // pub(crate) gilrs: Option<RefCell<gilrs::Gilrs>>,
 }
 
@@ -26,9 +41,36 @@ pub struct ContextOptions {
     pub window: window::Options,
     pub fixed_delta_time: f64,
     pub max_delta_time: f64,
+    /// Maximum number of [State::fixed_update] calls done to catch up in a single frame.
+    /// Time that did not fit is dropped, see [Geng::dropped_time].
+    /// Unlimited by default, so every step is run no matter how far behind
+    pub max_fixed_updates_per_frame: usize,
+    /// Limit the frame rate, see [Window::set_max_fps]
+    pub max_fps: Option<f64>,
+    /// Enable puffin profiler scopes and show the profiler panel of the debug overlay
+    pub profiler: bool,
+    /// Add the debug overlay (F3) to [Geng::run_state]
+    pub debug_overlay: bool,
     pub shader_prefix: Option<(String, String)>,
     pub target_ui_resolution: Option<vec2<f64>>,
+    /// Scale ui of [Geng::run_state] by the window scale factor, so that it is sized in logical pixels
+    pub ui_logical_pixels: bool,
     pub hot_reload: bool,
+    /// Record input into this file, see [Geng::record_input]
+    pub record_input: Option<std::path::PathBuf>,
+    /// Replay input from this file, see [Geng::replay_input]
+    pub replay_input: Option<std::path::PathBuf>,
+    /// Capture frames from the start, see [Geng::start_capture].
+    /// Also used as default options when toggling capture from the debug overlay
+    pub capture: Option<CaptureOptions>,
+    /// Initial language, detected from the system if not set, see [Geng::set_language]
+    pub language: Option<String>,
+    /// Settings loaded with [ContextOptions::with_saved_settings].
+    /// If set, the settings are saved on exit, including when the window is closed
+    pub settings: Option<settings::Settings>,
+    /// Arguments applied with [ContextOptions::with_cli],
+    /// kept so that they still take precedence over settings applied later
+    pub cli: Option<CliArgs>,
 }
 
 impl Default for ContextOptions {
@@ -41,15 +83,33 @@ impl Default for ContextOptions {
                 transparency: false,
                 mouse_passthrough: false,
                 size: None,
+                position: None,
+                min_size: None,
+                max_size: None,
+                resizable: true,
+                maximized: false,
                 fullscreen: !cfg!(debug_assertions),
                 auto_close: true,
                 start_hidden: false,
+                headless: false,
+                headless_fps: None,
             },
             fixed_delta_time: 0.05,
             max_delta_time: 0.1,
+            max_fixed_updates_per_frame: usize::MAX,
+            max_fps: None,
+            profiler: false,
+            debug_overlay: true,
             shader_prefix: None,
             target_ui_resolution: None,
+            ui_logical_pixels: false,
             hot_reload: cfg!(debug_assertions),
+            record_input: None,
+            replay_input: None,
+            capture: None,
+            language: None,
+            settings: None,
+            cli: None,
         }
     }
 }
@@ -81,6 +141,14 @@ impl Geng {
         setup_panic_handler();
         window::run(&options.window.clone(), |window| async move {
             let ugli = window.ugli().clone();
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                set_crash_report_info(
+                    "GL version",
+                    format!("{:?}", ugli.raw().get_version_string()),
+                );
+                set_crash_report_info("Context options", format!("{options:#?}"));
+            }
             let shader_lib = shader::Library::new(
                 &ugli,
                 options.window.antialias,
@@ -106,9 +174,27 @@ impl Geng {
                     default_font,
                     fixed_delta_time: Cell::new(options.fixed_delta_time),
                     max_delta_time: Cell::new(options.max_delta_time),
+                    max_fixed_updates_per_frame: Cell::new(options.max_fixed_updates_per_frame),
+                    fixed_update_alpha: Cell::new(0.0),
+                    dropped_time: Cell::new(0.0),
                     ui_theme: RefCell::new(None),
                     options,
-                    load_progress: RefCell::new(asset::LoadProgress::new()),
+                    loading_progress: RefCell::new(LoadingProgress::default()),
+                    clock: RefCell::new(Box::new(Timer::new())),
+                    input_session: RefCell::new(None),
+                    capture: RefCell::new(None),
+                    frame_index: Cell::new(0),
+                    new_overlays: RefCell::new(Vec::new()),
+                    settings: RefCell::new(options.settings.clone().unwrap_or_default()),
+                    localization: RefCell::new(None),
+                    language: RefCell::new(
+                        options
+                            .language
+                            .clone()
+                            .unwrap_or_else(batbox_i18n::detect_language),
+                    ),
+                    #[cfg(feature = "audio")]
+                    sound_types: RefCell::new(std::collections::HashMap::new()),
                     // gilrs: if cfg!(target_os = "android") {
                     //     None
                     // } else {
@@ -116,10 +202,53 @@ impl Geng {
                     // },
                 }),
             };
-            f(geng).await;
+            geng.window().set_max_fps(geng.inner.options.max_fps);
+            #[cfg(feature = "audio")]
+            if let Some(volume) = geng.inner.settings.borrow().master_volume {
+                geng.audio().master_volume().set_value(volume);
+            }
+            if geng.inner.options.debug_overlay {
+                geng.add_overlay(DebugOverlay::new(&geng));
+            }
+            if let Some(path) = &geng.inner.options.replay_input {
+                geng.replay_input(path)
+                    .expect("Failed to start replaying input");
+            }
+            if let Some(path) = &geng.inner.options.record_input {
+                geng.record_input(path)
+                    .expect("Failed to start recording input");
+            }
+            if let Some(options) = &geng.inner.options.capture {
+                geng.start_capture(options.clone())
+                    .expect("Failed to start capturing frames");
+            }
+            // Closing the window stops the app without letting the main future finish
+            geng.window()
+                .spawn({
+                    let geng = geng.clone();
+                    async move {
+                        let mut events = geng.window().events();
+                        while let Some(event) = events.next().await {
+                            if event == Event::CloseRequested && geng.window().is_auto_close() {
+                                geng.shutdown();
+                            }
+                        }
+                    }
+                })
+                .detach();
+            f(geng.clone()).await;
+            geng.shutdown();
         });
     }
 
+    /// Finish what must not be lost on exit, both when the app finishes and when the window is closed
+    fn shutdown(&self) {
+        self.stop_capture();
+        if self.inner.options.settings.is_some() {
+            self.save_settings();
+        }
+    }
+
     pub fn window(&self) -> &Window {
         &self.inner.window
     }
@@ -171,6 +300,30 @@ impl Geng {
     pub fn draw2d(&self) -> &draw2d::Helper {
         &self.inner.draw2d
     }
+
+    /// Shared [draw2d::Helper], for things keeping it like [async_state::transitions::Animation]
+    pub fn shared_draw2d(&self) -> &Rc<draw2d::Helper> {
+        &self.inner.draw2d
+    }
+
+    /// How far current frame is between the last fixed update and the next one, in range `[0, 1)`.
+    ///
+    /// Use it in [State::draw] to interpolate between previous and current fixed update state.
+    pub fn fixed_update_alpha(&self) -> f64 {
+        self.inner.fixed_update_alpha.get()
+    }
+
+    /// Time that was dropped during last frame because
+    /// [ContextOptions::max_fixed_updates_per_frame] was reached
+    pub fn dropped_time(&self) -> f64 {
+        self.inner.dropped_time.get()
+    }
+
+    pub fn set_max_fixed_updates_per_frame(&self, max_fixed_updates: usize) {
+        self.inner
+            .max_fixed_updates_per_frame
+            .set(max_fixed_updates);
+    }
 }
 
 impl Geng {
@@ -180,37 +333,99 @@ impl Geng {
         let geng = self;
         struct StateWrapper {
             state_manager: state::Manager,
-            debug_overlay: geng_debug_overlay::DebugOverlay,
+            overlays_below: Vec<Box<dyn Overlay>>,
+            overlays_above: Vec<Box<dyn Overlay>>,
         }
         impl StateWrapper {
             fn update(&mut self, delta_time: f64) {
+                for overlay in &mut self.overlays_below {
+                    overlay.update(delta_time);
+                }
                 self.state_manager.update(delta_time);
-                self.debug_overlay.update(delta_time);
+                for overlay in &mut self.overlays_above {
+                    overlay.update(delta_time);
+                }
             }
             fn fixed_update(&mut self, delta_time: f64) {
+                for overlay in &mut self.overlays_below {
+                    overlay.fixed_update(delta_time);
+                }
                 self.state_manager.fixed_update(delta_time);
-                self.debug_overlay.fixed_update(delta_time);
+                for overlay in &mut self.overlays_above {
+                    overlay.fixed_update(delta_time);
+                }
             }
             fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+                for overlay in &mut self.overlays_below {
+                    overlay.draw(framebuffer);
+                }
                 self.state_manager.draw(framebuffer);
-                self.debug_overlay.draw(framebuffer);
+                for overlay in &mut self.overlays_above {
+                    overlay.draw(framebuffer);
+                }
             }
             fn handle_event(&mut self, event: Event) {
-                self.debug_overlay
-                    .handle_event(event, |event| self.state_manager.handle_event(event));
+                // Top-most overlay gets the event first
+                fn chain(
+                    overlays: &mut [Box<dyn Overlay>],
+                    event: Event,
+                    last: &mut dyn FnMut(Event),
+                ) {
+                    match overlays.split_last_mut() {
+                        Some((top, rest)) => {
+                            top.handle_event(event, &mut |event| chain(rest, event, last))
+                        }
+                        None => last(event),
+                    }
+                }
+                let Self {
+                    state_manager,
+                    overlays_below,
+                    overlays_above,
+                } = self;
+                chain(overlays_above, event, &mut |event| {
+                    if !state_manager.handle_event(event.clone()) {
+                        chain(overlays_below, event, &mut |_| {});
+                    }
+                });
             }
             fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> impl ui::Widget + 'a {
-                ui::stack![self.state_manager.ui(cx), self.debug_overlay.ui(cx)]
+                let mut widgets: Vec<Box<dyn ui::Widget + 'a>> = Vec::new();
+                widgets.extend(self.overlays_below.iter_mut().map(|overlay| overlay.ui(cx)));
+                widgets.push(self.state_manager.ui(cx));
+                widgets.extend(self.overlays_above.iter_mut().map(|overlay| overlay.ui(cx)));
+                ui::stack(widgets)
             }
             fn transition(&mut self) -> Option<state::Transition> {
                 self.state_manager.transition()
             }
+            /// Pick up overlays added since last frame
+            fn add_new_overlays(&mut self, geng: &Geng) {
+                for (position, overlay) in geng.inner.new_overlays.borrow_mut().drain(..) {
+                    match position {
+                        OverlayPosition::Above => self.overlays_above.push(overlay),
+                        OverlayPosition::Below => self.overlays_below.push(overlay),
+                    }
+                }
+            }
+            /// Give overlays back so that next [Geng::run_state] keeps them
+            fn return_overlays(&mut self, geng: &Geng) {
+                let mut new_overlays = geng.inner.new_overlays.borrow_mut();
+                let returned = std::mem::take(&mut self.overlays_below)
+                    .into_iter()
+                    .map(|overlay| (OverlayPosition::Below, overlay))
+                    .chain(
+                        std::mem::take(&mut self.overlays_above)
+                            .into_iter()
+                            .map(|overlay| (OverlayPosition::Above, overlay)),
+                    );
+                new_overlays.splice(0..0, returned);
+            }
         }
         struct Runner {
             geng: Geng,
             state: StateWrapper,
             ui_controller: ui::Controller,
-            timer: Timer,
             next_fixed_update: f64,
         }
         let mut runner = Runner {
@@ -221,33 +436,69 @@ impl Geng {
                     state_manager.push(Box::new(state));
                     state_manager
                 },
-                debug_overlay: geng_debug_overlay::DebugOverlay::new(geng.window()),
+                overlays_below: Vec::new(),
+                overlays_above: Vec::new(),
+            },
+            ui_controller: {
+                let mut controller = ui::Controller::new(
+                    geng.ugli(),
+                    geng.ui_theme(),
+                    geng.inner.options.target_ui_resolution,
+                );
+                if geng.inner.options.ui_logical_pixels {
+                    controller.set_scale_factor(geng.window().scale_factor());
+                }
+                let geng = geng.clone();
+                controller.set_translate(move |key| geng.translate(key));
+                controller
             },
-            ui_controller: ui::Controller::new(
-                geng.ugli(),
-                geng.ui_theme(),
-                geng.inner.options.target_ui_resolution,
-            ),
-            timer: Timer::new(),
             next_fixed_update: geng.inner.fixed_delta_time.get(),
         };
 
         impl Runner {
             fn update(&mut self) {
-                let delta_time = self.timer.tick().as_secs_f64();
+                puffin::profile_function!();
+                let delta_time = self.geng.inner.clock.borrow_mut().tick();
                 let delta_time = delta_time.min(self.geng.inner.max_delta_time.get());
-                self.state.update(delta_time);
+                {
+                    puffin::profile_scope!("state update");
+                    self.state.update(delta_time);
+                }
                 self.ui_controller
                     .update(&mut self.state.ui(&self.ui_controller), delta_time);
                 self.next_fixed_update -= delta_time;
-                while self.next_fixed_update <= 0.0 {
-                    let delta_time = self.geng.inner.fixed_delta_time.get();
-                    self.next_fixed_update += delta_time;
-                    self.state.fixed_update(delta_time);
+                let fixed_delta_time = self.geng.inner.fixed_delta_time.get();
+                let max_fixed_updates = self.geng.inner.max_fixed_updates_per_frame.get();
+                let mut fixed_updates = 0;
+                while self.next_fixed_update <= 0.0 && fixed_updates < max_fixed_updates {
+                    puffin::profile_scope!("state fixed update");
+                    self.next_fixed_update += fixed_delta_time;
+                    self.state.fixed_update(fixed_delta_time);
+                    fixed_updates += 1;
                 }
+                let mut dropped_time = 0.0;
+                if self.next_fixed_update <= 0.0 {
+                    // Skip whole steps so that the phase of fixed updates is preserved
+                    let skipped_steps = (-self.next_fixed_update / fixed_delta_time).floor() + 1.0;
+                    dropped_time = skipped_steps * fixed_delta_time;
+                    self.next_fixed_update += dropped_time;
+                    log::debug!("Fixed update is falling behind, dropped {dropped_time:.3}s");
+                }
+                self.geng.inner.dropped_time.set(dropped_time);
+                self.geng
+                    .inner
+                    .fixed_update_alpha
+                    .set(1.0 - self.next_fixed_update / fixed_delta_time);
+                self.geng.end_frame(delta_time, fixed_updates);
             }
 
             fn handle_event(&mut self, event: Event) {
+                puffin::profile_function!();
+                if let Event::ScaleFactorChanged(scale_factor) = event {
+                    if self.geng.inner.options.ui_logical_pixels {
+                        self.ui_controller.set_scale_factor(scale_factor);
+                    }
+                }
                 if self
                     .ui_controller
                     .handle_event(&mut self.state.ui(&self.ui_controller), event.clone())
@@ -258,7 +509,11 @@ impl Geng {
             }
 
             fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
-                self.state.draw(framebuffer);
+                puffin::profile_function!();
+                {
+                    puffin::profile_scope!("state draw");
+                    self.state.draw(framebuffer);
+                }
                 self.ui_controller
                     .draw(&mut self.state.ui(&self.ui_controller), framebuffer);
             }
@@ -266,11 +521,12 @@ impl Geng {
             fn need_to_quit(&mut self) -> bool {
                 match self.state.transition() {
                     None => false,
-                    Some(state::Transition::Pop) => true,
+                    Some(state::Transition::Pop | state::Transition::PopWith(_)) => true,
                     _ => unreachable!(),
                 }
             }
         }
+        runner.state.add_new_overlays(geng);
         let mut events = geng.window().events();
         while let Some(event) = events.next().await {
             match event {
@@ -282,16 +538,21 @@ impl Geng {
                     //     }
                     // }
 
+                    puffin::GlobalProfiler::lock().new_frame();
+                    runner.state.add_new_overlays(geng);
                     runner.update();
                     let window_size = geng.inner.window.real_size();
                     // This means window is minimized?
                     if window_size.x != 0 && window_size.y != 0 {
                         geng.window().with_framebuffer(|framebuffer| {
                             runner.draw(framebuffer);
+                            geng.capture_frame(framebuffer);
                         });
                     }
+                    geng.inner.frame_index.set(geng.inner.frame_index.get() + 1);
 
                     if runner.need_to_quit() {
+                        runner.state.return_overlays(geng);
                         return;
                     }
                 }
@@ -0,0 +1,141 @@
+//! Crash reports written by the panic handler, see [setup_panic_handler].
+
+use super::*;
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of recent log lines included into a crash report
+const RECENT_LOG_LINES: usize = 100;
+
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static INFO: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+#[allow(clippy::type_complexity)]
+static HOOK: Mutex<Option<Box<dyn Fn(&mut CrashReport) + Send>>> = Mutex::new(None);
+
+struct RecentLogs;
+
+impl log::Log for RecentLogs {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+    fn log(&self, record: &log::Record) {
+        let Ok(mut logs) = RECENT_LOGS.lock() else {
+            return;
+        };
+        logs.push_back(format!(
+            "{} [{}] {}",
+            record.level(),
+            record.target(),
+            record.args(),
+        ));
+        while logs.len() > RECENT_LOG_LINES {
+            logs.pop_front();
+        }
+    }
+    fn flush(&self) {}
+}
+
+/// Report about a panic, written to a file by the panic handler
+#[derive(Debug)]
+pub struct CrashReport {
+    /// File the report is going to be written to
+    pub path: std::path::PathBuf,
+    pub message: String,
+    pub backtrace: String,
+    pub recent_logs: Vec<String>,
+    /// Titled sections, like system info or application specific data
+    pub sections: Vec<(String, String)>,
+}
+
+impl CrashReport {
+    pub fn add_section(&mut self, title: impl Into<String>, content: impl Into<String>) {
+        self.sections.push((title.into(), content.into()));
+    }
+
+    fn write(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        writeln!(writer, "{}", self.message)?;
+        for (title, content) in &self.sections {
+            writeln!(writer, "\n== {title} ==\n{content}")?;
+        }
+        writeln!(writer, "\n== Backtrace ==\n{}", self.backtrace)?;
+        writeln!(writer, "\n== Recent logs ==")?;
+        for line in &self.recent_logs {
+            writeln!(writer, "{line}")?;
+        }
+        writer.flush()
+    }
+}
+
+/// Set a hook called before writing a crash report,
+/// to attach application data or to show the error to the user
+pub fn set_crash_report_hook(hook: impl Fn(&mut CrashReport) + Send + 'static) {
+    *HOOK.lock().unwrap() = Some(Box::new(hook));
+}
+
+/// Set a section included in every crash report, replacing previous content with same title
+pub fn set_crash_report_info(title: impl Into<String>, content: impl Into<String>) {
+    let title = title.into();
+    let content = content.into();
+    let mut info = INFO.lock().unwrap();
+    match info.iter_mut().find(|(existing, _)| *existing == title) {
+        Some((_, existing)) => *existing = content,
+        None => info.push((title, content)),
+    }
+}
+
+/// Set up panic hook writing a [CrashReport] into `crash-reports` directory
+/// including backtrace, recent logs and info set with [set_crash_report_info]
+pub fn setup_panic_handler() {
+    static SETUP: std::sync::Once = std::sync::Once::new();
+    SETUP.call_once(|| {
+        logger::add_logger(Box::new(RecentLogs));
+        set_crash_report_info(
+            "System",
+            format!(
+                "geng {}\n{} {}",
+                env!("CARGO_PKG_VERSION"),
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+            ),
+        );
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let mut report = CrashReport {
+                path: preferences::base_path()
+                    .join("crash-reports")
+                    .join(format!("crash-{timestamp}.txt")),
+                message: info.to_string(),
+                backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+                // try_lock since we might be panicking while holding the lock
+                recent_logs: RECENT_LOGS
+                    .try_lock()
+                    .map(|logs| logs.iter().cloned().collect())
+                    .unwrap_or_default(),
+                sections: INFO.try_lock().map(|info| info.clone()).unwrap_or_default(),
+            };
+            if let Ok(hook) = HOOK.try_lock() {
+                if let Some(hook) = &*hook {
+                    hook(&mut report);
+                }
+            }
+            let written = report
+                .path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::File::create(&report.path))
+                .and_then(|file| report.write(std::io::BufWriter::new(file)));
+            // Not using the logger since the panic could have happened while logging,
+            // and stderr is where the default hook prints the panic message anyway
+            match written {
+                Ok(()) => eprintln!("Crash report written to {:?}", report.path),
+                Err(e) => eprintln!("Failed to write crash report: {e}"),
+            }
+            default_hook(info);
+        }));
+    });
+}
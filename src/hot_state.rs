@@ -0,0 +1,206 @@
+//! Reloading game logic from a dynamic library, see [HotReloadState].
+
+use super::*;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Name of the function exported by [hot_state!]
+const SYMBOL: &[u8] = b"geng_hot_state";
+
+/// Time without changes to the library before reloading, since it is written in several steps
+const RELOAD_DELAY: f64 = 0.5;
+
+/// State that can be moved across a reload of [HotReloadState]
+pub trait HotState: State {
+    fn save(&self) -> anyhow::Result<Vec<u8>>;
+}
+
+impl<T: State + Serialize> HotState for T {
+    fn save(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+}
+
+type NewHotState = fn(&Geng, Option<&[u8]>) -> Box<dyn HotState>;
+
+/// Used by [hot_state!]
+#[doc(hidden)]
+pub fn new_hot_state<T: HotState + serde::de::DeserializeOwned>(
+    geng: &Geng,
+    saved: Option<&[u8]>,
+    new: impl FnOnce(&Geng) -> T,
+) -> Box<dyn HotState> {
+    let restored = saved.and_then(|data| match serde_json::from_slice::<T>(data) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            log::error!("Failed to restore state after reload, starting anew: {e}");
+            None
+        }
+    });
+    Box::new(restored.unwrap_or_else(|| new(geng)))
+}
+
+/// Export the state from a `cdylib` to be loaded with [HotReloadState].
+///
+/// The state is created with the given function, or restored by deserializing after a reload.
+///
+/// ```ignore
+/// geng::hot_state!(Game, Game::new);
+/// ```
+#[macro_export]
+macro_rules! hot_state {
+    ($state:ty, $new:expr) => {
+        #[no_mangle]
+        pub fn geng_hot_state(
+            geng: &$crate::Geng,
+            saved: Option<&[u8]>,
+        ) -> Box<dyn $crate::HotState> {
+            $crate::new_hot_state::<$state>(geng, saved, $new)
+        }
+    };
+}
+
+/// State living in a dynamic library exported with [hot_state!],
+/// reloaded when the library is rebuilt.
+///
+/// The state is moved across reloads by serializing it with serde,
+/// so fields added with `#[serde(default)]` keep the rest of the state.
+/// Both the library and the application must be built with the same compiler and geng version.
+/// Libraries are never unloaded since states created by the old code may still be alive.
+pub struct HotReloadState {
+    geng: Geng,
+    path: PathBuf,
+    /// Copy of the library currently in use
+    copy: PathBuf,
+    generation: usize,
+    state: Box<dyn HotState>,
+    need_reload: Arc<AtomicBool>,
+    reload_timer: Option<Timer>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl HotReloadState {
+    /// Load the state from the library, like `target/debug/libgame.so`
+    pub fn new(geng: &Geng, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let need_reload = Arc::new(AtomicBool::new(false));
+        let watcher = asset::hot::watch(&path, need_reload.clone())?;
+        let (new, copy) = load(&path, 0)?;
+        Ok(Self {
+            geng: geng.clone(),
+            state: new(geng, None),
+            path,
+            copy,
+            generation: 0,
+            need_reload,
+            reload_timer: None,
+            _watcher: watcher,
+        })
+    }
+
+    fn reload(&mut self) -> anyhow::Result<()> {
+        let saved = match self.state.save() {
+            Ok(saved) => Some(saved),
+            Err(e) => {
+                log::error!("Failed to save state before reload, starting anew: {e}");
+                None
+            }
+        };
+        let (new, copy) = load(&self.path, self.generation + 1)?;
+        self.generation += 1;
+        self.state = new(&self.geng, saved.as_deref());
+        remove_copy(&std::mem::replace(&mut self.copy, copy));
+        log::info!("Reloaded {:?}", self.path);
+        Ok(())
+    }
+}
+
+impl Drop for HotReloadState {
+    fn drop(&mut self) {
+        remove_copy(&self.copy);
+    }
+}
+
+/// Remove a copy made by [load], the library stays loaded
+/// (removing may fail on platforms that lock loaded libraries)
+fn remove_copy(copy: &Path) {
+    if let Err(e) = std::fs::remove_file(copy) {
+        log::debug!("Failed to remove {copy:?}: {e}");
+    }
+}
+
+/// Load a copy of the library since loading the same path again would return the old one.
+///
+/// Returns the path of the copy to remove when it is not used anymore
+fn load(path: &Path, generation: usize) -> anyhow::Result<(NewHotState, PathBuf)> {
+    let mut copy_name = path.file_stem().unwrap_or_default().to_owned();
+    copy_name.push(format!("-{}-{generation}", std::process::id()));
+    let mut copy = std::env::temp_dir().join(copy_name);
+    if let Some(extension) = path.extension() {
+        copy.set_extension(extension);
+    }
+    std::fs::copy(path, &copy)?;
+    let load_copy = || -> anyhow::Result<NewHotState> {
+        let library = unsafe { libloading::Library::new(&copy)? };
+        let new = unsafe { *library.get::<NewHotState>(SYMBOL)? };
+        // Never unloading the library, see HotReloadState docs
+        std::mem::forget(library);
+        Ok(new)
+    };
+    match load_copy() {
+        Ok(new) => Ok((new, copy)),
+        Err(e) => {
+            remove_copy(&copy);
+            Err(e)
+        }
+    }
+}
+
+impl State for HotReloadState {
+    fn update(&mut self, delta_time: f64) {
+        if self.need_reload.swap(false, Ordering::SeqCst) {
+            self.reload_timer = Some(Timer::new());
+        }
+        if let Some(timer) = &self.reload_timer {
+            if timer.elapsed().as_secs_f64() > RELOAD_DELAY {
+                self.reload_timer = None;
+                if let Err(e) = self.reload() {
+                    log::error!("Failed to reload {:?}: {e}", self.path);
+                }
+            }
+        }
+        self.state.update(delta_time);
+    }
+    fn fixed_update(&mut self, delta_time: f64) {
+        self.state.fixed_update(delta_time);
+    }
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.state.draw(framebuffer);
+    }
+    fn handle_event(&mut self, event: Event) -> bool {
+        self.state.handle_event(event)
+    }
+    fn transition(&mut self) -> Option<state::Transition> {
+        self.state.transition()
+    }
+    fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
+        self.state.ui(cx)
+    }
+    fn on_enter(&mut self) {
+        self.state.on_enter();
+    }
+    fn on_exit(&mut self) {
+        self.state.on_exit();
+    }
+    fn on_pause(&mut self) {
+        self.state.on_pause();
+    }
+    fn on_resume(&mut self, result: Option<Box<dyn std::any::Any>>) {
+        self.state.on_resume(result);
+    }
+    fn overlay(&self) -> Option<state::OverlayOptions> {
+        self.state.overlay()
+    }
+}
@@ -41,16 +41,28 @@ use wasm_bindgen::prelude::*;
 
 pub use image;
 
+mod capture;
 mod cli_args;
 mod context;
+#[cfg(not(target_arch = "wasm32"))]
+mod crash_report;
+#[cfg(all(feature = "hot-state", not(target_arch = "wasm32")))]
+mod hot_state;
 mod loading_screen;
+mod localization;
+mod overlay;
+mod replay;
+pub mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
 
 pub use geng_asset as asset;
 pub use geng_async_state as async_state;
 #[cfg(feature = "audio")]
 pub use geng_audio::{self as audio, *};
 pub use geng_camera::{
-    self as camera, AbstractCamera2d, AbstractCamera3d, Camera2d, Camera2dFov, PixelPerfectCamera,
+    self as camera, AbstractCamera2d, AbstractCamera3d, Camera2d, Camera2dFov, LogicalPixelCamera,
+    PixelPerfectCamera,
 };
 pub use geng_draw2d::{self as draw2d, Draw2d};
 pub use geng_font::{self as font, Font, TextAlign};
@@ -61,25 +73,17 @@ pub use geng_texture_atlas::{self as texture_atlas, TextureAtlas};
 pub use geng_ui as ui;
 pub use geng_window::{self as window, CursorType, Event, Key, MouseButton, Touch, Window};
 
+pub use capture::*;
 pub use cli_args::*;
 pub use context::*;
-pub use loading_screen::*;
-
 #[cfg(not(target_arch = "wasm32"))]
-pub fn setup_panic_handler() {
-    let default_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |info: &std::panic::PanicInfo| {
-        let log_file_path = preferences::base_path().join("error.txt");
-        if let Ok(mut file) = std::fs::OpenOptions::new()
-            // .append(true)
-            .create(true)
-            .open(log_file_path)
-        {
-            let _ = writeln!(file, "{info}");
-        }
-        default_hook(info);
-    }));
-}
+pub use crash_report::*;
+#[cfg(all(feature = "hot-state", not(target_arch = "wasm32")))]
+pub use hot_state::*;
+pub use loading_screen::*;
+pub use localization::*;
+pub use overlay::*;
+pub use replay::*;
 
 #[cfg(target_arch = "wasm32")]
 pub fn setup_panic_handler() {
@@ -116,7 +120,7 @@ pub fn setup_panic_handler() {
 impl Geng {
     pub(crate) fn set_loading_progress_title(&self, title: &str) {
         log::trace!("Set loading progress title to {title:?}");
-        // TODO: native
+        title.clone_into(&mut self.inner.loading_progress.borrow_mut().title);
         #[cfg(target_arch = "wasm32")]
         {
             #[wasm_bindgen(inline_js = r#"
@@ -133,7 +137,11 @@ impl Geng {
 
     pub(crate) fn set_loading_progress(&self, progress: f64, total: Option<f64>) {
         log::trace!("Loading progress {progress:?}/{total:?}");
-        // TODO: native
+        {
+            let mut loading_progress = self.inner.loading_progress.borrow_mut();
+            loading_progress.progress = progress;
+            loading_progress.total = total;
+        }
         #[cfg(target_arch = "wasm32")]
         {
             #[wasm_bindgen(inline_js = r#"
@@ -5,6 +5,8 @@ pub struct Vertex {
     a_pos: vec2<f32>,
 }
 
+/// Loading screen that reports progress to the html page on the web,
+/// and draws a simple progress bar on native
 pub struct EmptyLoadingScreen {
     geng: Geng,
 }
@@ -12,12 +14,16 @@ pub struct EmptyLoadingScreen {
 impl EmptyLoadingScreen {
     pub fn new(geng: &Geng) -> Self {
         geng.set_loading_progress_title("Loading assets"); // TODO
-        geng.set_loading_progress(0.0, None); // TODO
+        geng.set_loading_progress(0.0, None);
         Self { geng: geng.clone() }
     }
 }
 
-impl ProgressScreen for EmptyLoadingScreen {}
+impl ProgressScreen for EmptyLoadingScreen {
+    fn update_progress(&mut self, progress: f64) {
+        self.geng.set_loading_progress(progress, Some(1.0));
+    }
+}
 
 impl Drop for EmptyLoadingScreen {
     fn drop(&mut self) {
@@ -26,10 +32,40 @@ impl Drop for EmptyLoadingScreen {
 }
 
 impl State for EmptyLoadingScreen {
-    fn update(&mut self, _delta_time: f64) {
-        let progress = self.geng.inner.load_progress.borrow();
-        self.geng
-            .set_loading_progress(progress.progress as f64, Some(progress.total as f64));
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        #[cfg(target_arch = "wasm32")]
+        let _ = framebuffer;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+            let loading_progress = self.geng.inner.loading_progress.borrow();
+            let framebuffer_size = framebuffer.size().map(|x| x as f32);
+            let bar_size = vec2((framebuffer_size.x * 0.5).min(400.0), 16.0);
+            let bar = Aabb2::point(framebuffer_size / 2.0).extend_symmetric(bar_size / 2.0);
+            let camera = &PixelPerfectCamera;
+            let draw2d = self.geng.draw2d();
+            draw2d.quad(framebuffer, camera, bar.extend_uniform(2.0), Rgba::GRAY);
+            draw2d.quad(framebuffer, camera, bar, Rgba::BLACK);
+            if let Some(total) = loading_progress.total {
+                let progress = if total > 0.0 {
+                    (loading_progress.progress / total).clamp(0.0, 1.0) as f32
+                } else {
+                    1.0
+                };
+                let filled = Aabb2 {
+                    max: vec2(bar.min.x + bar.width() * progress, bar.max.y),
+                    ..bar
+                };
+                draw2d.quad(framebuffer, camera, filled, Rgba::WHITE);
+            }
+            self.geng.default_font().draw(
+                framebuffer,
+                camera,
+                &loading_progress.title,
+                vec2(TextAlign::CENTER, TextAlign::BOTTOM),
+                mat3::translate(vec2(bar.center().x, bar.max.y + 16.0)) * mat3::scale_uniform(24.0),
+                Rgba::WHITE,
+            );
+        }
     }
-    fn draw(&mut self, _framebuffer: &mut ugli::Framebuffer) {}
 }
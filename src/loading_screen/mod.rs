@@ -12,13 +12,24 @@ pub trait ProgressScreen: State {
 
 impl ProgressScreen for state::Empty {}
 
+/// Last values passed to [Geng::set_loading_progress] and [Geng::set_loading_progress_title]
+#[derive(Debug, Default)]
+pub(crate) struct LoadingProgress {
+    pub title: String,
+    pub progress: f64,
+    pub total: Option<f64>,
+}
+
 pub struct LoadingScreen<L, G>
 where
     L: ProgressScreen,
     G: State,
 {
+    geng: Geng,
     future: Pin<Box<dyn Future<Output = G>>>,
     state: L,
+    /// Asset manager counters at the moment loading screen was created
+    initial_progress: asset::LoadProgress,
 }
 
 impl<L, G> LoadingScreen<L, G>
@@ -26,14 +37,12 @@ where
     L: ProgressScreen,
     G: State,
 {
-    pub fn new<F: Future<Output = G> + 'static>(
-        #[allow(unused_variables)] geng: &Geng,
-        state: L,
-        future: F,
-    ) -> Self {
+    pub fn new<F: Future<Output = G> + 'static>(geng: &Geng, state: L, future: F) -> Self {
         LoadingScreen {
+            geng: geng.clone(),
             future: future.boxed_local(),
             state,
+            initial_progress: geng.asset_manager().load_progress(),
         }
     }
 }
@@ -45,7 +54,15 @@ where
 {
     fn update(&mut self, delta_time: f64) {
         self.state.update(delta_time);
-        // TODO: state.update_progress(future.progress().unwrap());
+        let progress = self.geng.asset_manager().load_progress();
+        let total = progress.total - self.initial_progress.total;
+        if total != 0 {
+            let finished = progress
+                .progress
+                .saturating_sub(self.initial_progress.progress);
+            self.state
+                .update_progress((finished as f64 / total as f64).min(1.0));
+        }
     }
     fn fixed_update(&mut self, delta_time: f64) {
         self.state.fixed_update(delta_time);
@@ -53,8 +70,8 @@ where
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
         self.state.draw(framebuffer);
     }
-    fn handle_event(&mut self, event: Event) {
-        self.state.handle_event(event);
+    fn handle_event(&mut self, event: Event) -> bool {
+        self.state.handle_event(event)
     }
     fn transition(&mut self) -> Option<state::Transition> {
         if let std::task::Poll::Ready(state) =
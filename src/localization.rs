@@ -0,0 +1,92 @@
+//! Runtime localization, see [Localization] and [Geng::translate].
+
+use super::*;
+
+use std::collections::HashMap;
+
+/// Translation tables keyed by language, then by localization key.
+///
+/// Loaded as JSON or TOML (detected by extension), for example:
+/// ```json
+/// {
+///     "en": { "play": "Play", "quit": "Quit" },
+///     "ru": { "play": "Играть", "quit": "Выйти" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Localization {
+    languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Localization {
+    pub fn get(&self, language: &str, key: &str) -> Option<&str> {
+        self.languages.get(language)?.get(key).map(String::as_str)
+    }
+
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.languages.keys().map(String::as_str)
+    }
+}
+
+impl asset::Load for Localization {
+    type Options = ();
+    fn load(
+        manager: &asset::Manager,
+        path: &std::path::Path,
+        _options: &(),
+    ) -> asset::Future<Self> {
+        manager.load_serde(path)
+    }
+    const DEFAULT_EXT: Option<&'static str> = Some("json");
+}
+
+/// Source of translations set with [Geng::set_localization]
+pub trait Translate: 'static {
+    fn translate(&self, language: &str, key: &str) -> Option<String>;
+}
+
+impl Translate for Localization {
+    fn translate(&self, language: &str, key: &str) -> Option<String> {
+        self.get(language, key).map(str::to_owned)
+    }
+}
+
+impl<T: Translate + asset::Load> Translate for asset::Hot<T> {
+    fn translate(&self, language: &str, key: &str) -> Option<String> {
+        self.get().translate(language, key)
+    }
+}
+
+impl<T: Translate> Translate for Rc<T> {
+    fn translate(&self, language: &str, key: &str) -> Option<String> {
+        T::translate(self, language, key)
+    }
+}
+
+impl Geng {
+    /// Set translations used by [Geng::translate] and localized ui widgets.
+    ///
+    /// Pass [asset::Hot] to have translations reloaded when the file changes.
+    pub fn set_localization(&self, localization: impl Translate) {
+        *self.inner.localization.borrow_mut() = Some(Rc::new(localization));
+    }
+
+    /// Current language, detected from the system unless set with [ContextOptions::language]
+    pub fn language(&self) -> String {
+        self.inner.language.borrow().clone()
+    }
+
+    /// Change current language, localized ui widgets update on next frame
+    pub fn set_language(&self, language: &str) {
+        language.clone_into(&mut self.inner.language.borrow_mut());
+    }
+
+    /// Translate localization key into current language, or return the key if there is no translation
+    pub fn translate(&self, key: &str) -> String {
+        let localization = self.inner.localization.borrow().clone();
+        localization
+            .and_then(|localization| localization.translate(&self.language(), key))
+            .unwrap_or_else(|| key.to_owned())
+    }
+}
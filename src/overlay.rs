@@ -0,0 +1,99 @@
+use super::*;
+
+/// App-wide layer run by [Geng::run_state] together with the state stack,
+/// like notifications, pause menu or a custom console.
+///
+/// Overlays added with [Geng::add_overlay] are drawn on top of the state
+/// and receive events before it, so they can consume them by not calling `next`.
+/// Overlays added with [Geng::add_overlay_below] are drawn under the state
+/// and receive events after it.
+pub trait Overlay: 'static {
+    fn update(&mut self, delta_time: f64) {
+        #![allow(unused_variables)]
+    }
+
+    fn fixed_update(&mut self, delta_time: f64) {
+        #![allow(unused_variables)]
+    }
+
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        #![allow(unused_variables)]
+    }
+
+    /// Call `next` to pass the event (or any other events) to the layers below
+    fn handle_event(&mut self, event: Event, next: &mut dyn FnMut(Event)) {
+        next(event);
+    }
+
+    fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
+        #![allow(unused_variables)]
+        Box::new(ui::Void)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum OverlayPosition {
+    Above,
+    Below,
+}
+
+impl Geng {
+    /// Add overlay above the state stack, on top of previously added overlays
+    pub fn add_overlay(&self, overlay: impl Overlay) {
+        self.inner
+            .new_overlays
+            .borrow_mut()
+            .push((OverlayPosition::Above, Box::new(overlay)));
+    }
+
+    /// Add overlay below the state stack, on top of previously added overlays below
+    pub fn add_overlay_below(&self, overlay: impl Overlay) {
+        self.inner
+            .new_overlays
+            .borrow_mut()
+            .push((OverlayPosition::Below, Box::new(overlay)));
+    }
+}
+
+/// [geng_debug_overlay::DebugOverlay] as an overlay, see [ContextOptions::debug_overlay]
+pub(crate) struct DebugOverlay {
+    // Weak since overlays are stored in Geng
+    geng: std::rc::Weak<GengImpl>,
+    inner: geng_debug_overlay::DebugOverlay,
+}
+
+impl DebugOverlay {
+    pub fn new(geng: &Geng) -> Self {
+        let mut inner = geng_debug_overlay::DebugOverlay::new(geng.window());
+        if geng.inner.options.profiler {
+            inner.set_profiler_enabled(true);
+        }
+        Self {
+            geng: Rc::downgrade(&geng.inner),
+            inner,
+        }
+    }
+}
+
+impl Overlay for DebugOverlay {
+    fn update(&mut self, delta_time: f64) {
+        self.inner.update(delta_time);
+    }
+    fn fixed_update(&mut self, delta_time: f64) {
+        self.inner.fixed_update(delta_time);
+    }
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        self.inner.draw(framebuffer);
+    }
+    fn handle_event(&mut self, event: Event, next: &mut dyn FnMut(Event)) {
+        self.inner.handle_event(event, next);
+        if self.inner.take_capture_toggle() {
+            if let Some(inner) = self.geng.upgrade() {
+                Geng { inner }.toggle_capture();
+            }
+        }
+    }
+    fn ui<'a>(&'a mut self, cx: &'a ui::Controller) -> Box<dyn ui::Widget + 'a> {
+        self.inner.ui(cx)
+    }
+}
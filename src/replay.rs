@@ -0,0 +1,188 @@
+//! Recording and deterministic replay of input.
+//!
+//! Recording is a file with a json line per frame containing
+//! the events received, delta time and number of fixed updates.
+
+use super::*;
+
+/// Source of delta time used by [Geng::run_state]
+pub trait Clock {
+    /// Returns time in seconds since previous call
+    fn tick(&mut self) -> f64;
+}
+
+impl Clock for Timer {
+    fn tick(&mut self) -> f64 {
+        Timer::tick(self).as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Frame {
+    events: Vec<Event>,
+    delta_time: f64,
+    fixed_updates: usize,
+}
+
+pub(crate) struct Recorder {
+    writer: std::io::BufWriter<std::fs::File>,
+    events: Rc<RefCell<Vec<Event>>>,
+}
+
+impl Recorder {
+    fn record_frame(&mut self, delta_time: f64, fixed_updates: usize) -> anyhow::Result<()> {
+        let frame = Frame {
+            events: std::mem::take(&mut self.events.borrow_mut()),
+            delta_time,
+            fixed_updates,
+        };
+        serde_json::to_writer(&mut self.writer, &frame)?;
+        writeln!(self.writer)?;
+        // Flushing every frame so that we dont lose anything on crash
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub(crate) struct ReplayState {
+    frames: std::collections::VecDeque<Frame>,
+    current: Option<Frame>,
+}
+
+struct ReplayClock {
+    state: Rc<RefCell<ReplayState>>,
+    fallback: Timer,
+}
+
+impl Clock for ReplayClock {
+    fn tick(&mut self) -> f64 {
+        let fallback = self.fallback.tick().as_secs_f64();
+        match &self.state.borrow().current {
+            Some(frame) => frame.delta_time,
+            None => fallback,
+        }
+    }
+}
+
+pub(crate) enum Session {
+    Recording(Recorder),
+    Replaying(Rc<RefCell<ReplayState>>),
+}
+
+impl Geng {
+    /// Replace the clock used by [Geng::run_state] to calculate delta time
+    pub fn set_clock(&self, clock: impl Clock + 'static) {
+        *self.inner.clock.borrow_mut() = Box::new(clock);
+    }
+
+    /// Start recording input into a file.
+    ///
+    /// Not supported on the web since there is no file system
+    pub fn record_input(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if cfg!(target_arch = "wasm32") {
+            anyhow::bail!("Can not record input to {path:?}, not supported on the web");
+        }
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(e) => anyhow::bail!("Failed to create {path:?}: {e}"),
+        };
+        let events = Rc::new(RefCell::new(Vec::new()));
+        self.window().set_event_hook(Some(Box::new({
+            let events = events.clone();
+            move |event| {
+                if event != Event::Draw {
+                    events.borrow_mut().push(event.clone());
+                }
+                vec![event]
+            }
+        })));
+        *self.inner.input_session.borrow_mut() = Some(Session::Recording(Recorder {
+            writer: std::io::BufWriter::new(file),
+            events,
+        }));
+        log::info!("Recording input to {path:?}");
+        Ok(())
+    }
+
+    /// Replay input previously recorded with [Geng::record_input].
+    ///
+    /// Real input is ignored until the replay is finished (except for [Event::CloseRequested]).
+    /// Not supported on the web since there is no file system
+    pub fn replay_input(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if cfg!(target_arch = "wasm32") {
+            anyhow::bail!("Can not replay input from {path:?}, not supported on the web");
+        }
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) => anyhow::bail!("Failed to read {path:?}: {e}"),
+        };
+        let frames = data
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        let state = Rc::new(RefCell::new(ReplayState {
+            frames,
+            current: None,
+        }));
+        self.window().set_event_hook(Some(Box::new({
+            let state = state.clone();
+            move |event| {
+                let mut state = state.borrow_mut();
+                match event {
+                    Event::Draw => {
+                        state.current = state.frames.pop_front();
+                        match &state.current {
+                            Some(frame) => {
+                                frame.events.iter().cloned().chain([Event::Draw]).collect()
+                            }
+                            None => vec![Event::Draw],
+                        }
+                    }
+                    Event::CloseRequested => vec![event],
+                    _ if state.current.is_none() && state.frames.is_empty() => vec![event],
+                    _ => vec![],
+                }
+            }
+        })));
+        self.set_clock(ReplayClock {
+            state: state.clone(),
+            fallback: Timer::new(),
+        });
+        *self.inner.input_session.borrow_mut() = Some(Session::Replaying(state));
+        log::info!("Replaying input from {path:?}");
+        Ok(())
+    }
+
+    pub(crate) fn end_frame(&self, delta_time: f64, fixed_updates: usize) {
+        let mut session = self.inner.input_session.borrow_mut();
+        match &mut *session {
+            Some(Session::Recording(recorder)) => {
+                if let Err(e) = recorder.record_frame(delta_time, fixed_updates) {
+                    log::error!("Failed to record input: {e}");
+                    session.take();
+                    self.window().set_event_hook(None);
+                }
+            }
+            Some(Session::Replaying(state)) => {
+                let mut state = state.borrow_mut();
+                if let Some(frame) = &state.current {
+                    if frame.fixed_updates != fixed_updates {
+                        log::warn!(
+                            "Replay desync: expected {} fixed updates, got {fixed_updates}",
+                            frame.fixed_updates,
+                        );
+                    }
+                    if state.frames.is_empty() {
+                        log::info!("Replay finished");
+                        state.current = None;
+                        self.window().set_event_hook(None);
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! User-facing options persisted between launches, see [Settings].
+
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// Preferences key the settings are stored under
+const PREFERENCES_KEY: &str = "geng-settings";
+
+/// User-facing window, graphics and audio options saved with `batbox-preferences`.
+///
+/// Options that are `None` were never changed and are left as in [ContextOptions].
+/// The order of precedence is defaults, then saved settings, then command line,
+/// see [ContextOptions::with_saved_settings].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_size: Option<vec2<usize>>,
+    pub window_position: Option<vec2<i32>>,
+    pub fullscreen: Option<bool>,
+    pub vsync: Option<bool>,
+    /// Only applied on next launch
+    pub antialias: Option<bool>,
+    pub master_volume: Option<f32>,
+    pub language: Option<String>,
+    /// Volumes of sound types created with [Geng::sound_type], by name
+    pub volumes: BTreeMap<String, f32>,
+}
+
+impl Settings {
+    /// Load saved settings, or default if there are none
+    pub fn load() -> Self {
+        preferences::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        preferences::save(PREFERENCES_KEY, self);
+    }
+}
+
+impl ContextOptions {
+    /// Override window options with the ones set in the settings,
+    /// except for those given on the command line, see [ContextOptions::with_cli]
+    pub fn with_settings(&mut self, settings: &Settings) {
+        if let Some(size) = settings.window_size {
+            self.window.size = Some(size);
+        }
+        if let Some(position) = settings.window_position {
+            self.window.position = Some(position);
+        }
+        if let Some(fullscreen) = settings.fullscreen {
+            self.window.fullscreen = fullscreen;
+        }
+        if let Some(vsync) = settings.vsync {
+            self.window.vsync = vsync;
+        }
+        if let Some(antialias) = settings.antialias {
+            self.window.antialias = antialias;
+        }
+        if let Some(language) = &settings.language {
+            self.language = Some(language.clone());
+        }
+        // Command line takes precedence no matter which was applied first
+        if let Some(args) = self.cli.clone() {
+            self.with_cli(&args);
+        }
+    }
+
+    /// Load saved [Settings] and apply them, enabling saving them back on exit
+    pub fn with_saved_settings(&mut self) {
+        let settings = Settings::load();
+        self.with_settings(&settings);
+        self.settings = Some(settings);
+    }
+}
+
+impl Geng {
+    /// Current settings, including changes made while running like toggling fullscreen
+    pub fn settings(&self) -> Settings {
+        let mut settings = self.inner.settings.borrow().clone();
+        let window = self.window();
+        settings.fullscreen = Some(window.is_fullscreen());
+        if !window.is_fullscreen() {
+            settings.window_size = Some(window.real_size());
+            if let Some(position) = window.position() {
+                settings.window_position = Some(position);
+            }
+        }
+        settings.vsync = Some(window.vsync());
+        settings.language = Some(self.language());
+        #[cfg(feature = "audio")]
+        {
+            settings.master_volume = Some(self.audio().master_volume().value());
+            for (name, &sound_type) in &*self.inner.sound_types.borrow() {
+                settings
+                    .volumes
+                    .insert(name.clone(), self.audio().volume(sound_type).value());
+            }
+        }
+        settings
+    }
+
+    /// Apply settings to the running application, and remember those that need a restart
+    pub fn set_settings(&self, settings: Settings) {
+        let window = self.window();
+        if let Some(fullscreen) = settings.fullscreen {
+            window.set_fullscreen(fullscreen);
+        }
+        if let Some(vsync) = settings.vsync {
+            window.set_vsync(vsync);
+        }
+        if let Some(language) = &settings.language {
+            self.set_language(language);
+        }
+        #[cfg(feature = "audio")]
+        {
+            if let Some(volume) = settings.master_volume {
+                self.audio().master_volume().set_value(volume);
+            }
+            for (name, &sound_type) in &*self.inner.sound_types.borrow() {
+                if let Some(&volume) = settings.volumes.get(name) {
+                    self.audio().volume(sound_type).set_value(volume);
+                }
+            }
+        }
+        *self.inner.settings.borrow_mut() = settings;
+    }
+
+    /// Save current [Geng::settings]
+    pub fn save_settings(&self) {
+        self.settings().save();
+    }
+
+    /// Sound type identified by name, so that its volume is saved in [Settings].
+    ///
+    /// Returns same sound type when called with the same name.
+    #[cfg(feature = "audio")]
+    pub fn sound_type(&self, name: &str) -> SoundType {
+        if let Some(&sound_type) = self.inner.sound_types.borrow().get(name) {
+            return sound_type;
+        }
+        let sound_type = SoundType::new();
+        if let Some(&volume) = self.inner.settings.borrow().volumes.get(name) {
+            self.audio().volume(sound_type).set_value(volume);
+        }
+        self.inner
+            .sound_types
+            .borrow_mut()
+            .insert(name.to_owned(), sound_type);
+        sound_type
+    }
+}
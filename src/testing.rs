@@ -0,0 +1,217 @@
+//! Helpers for testing rendering against stored golden images.
+//!
+//! ```no_run
+//! geng::testing::run(|geng| async move {
+//!     let image = geng::testing::render_state(
+//!         &geng,
+//!         &mut geng::state::Empty,
+//!         &geng::testing::RenderOptions::default(),
+//!     );
+//!     geng::testing::check_golden(&image, "tests/golden/empty.png", 0).unwrap();
+//! });
+//! ```
+
+use super::*;
+
+/// Environment variable that makes [check_golden] overwrite stored images instead of comparing
+pub const UPDATE_GOLDEN_ENV: &str = "GENG_UPDATE_GOLDEN";
+
+/// Run geng headless, usable from `cargo test` without a display
+pub fn run<Fut>(f: impl 'static + FnOnce(Geng) -> Fut)
+where
+    Fut: 'static + Future<Output = ()>,
+{
+    let mut options = ContextOptions::default();
+    options.window.title = "Geng Test".to_owned();
+    options.window.headless = true;
+    options.window.fullscreen = false;
+    options.hot_reload = false;
+    Geng::run_with(&options, f);
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Size of the offscreen texture
+    pub size: vec2<usize>,
+    /// Number of frames to run before reading the pixels
+    pub frames: usize,
+    /// Delta time passed to [State::update] every frame
+    pub delta_time: f64,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            size: vec2(320, 240),
+            frames: 1,
+            delta_time: 1.0 / 60.0,
+        }
+    }
+}
+
+/// Render the state (including its ui) into an offscreen texture and read back the last frame
+pub fn render_state(
+    geng: &Geng,
+    state: &mut dyn State,
+    options: &RenderOptions,
+) -> image::RgbaImage {
+    let ugli = geng.ugli();
+    let mut texture = ugli::Texture::new_uninitialized(ugli, options.size);
+    let mut depth = ugli::Renderbuffer::<ugli::DepthStencilValue>::new(ugli, options.size);
+    let ui_controller = ui::Controller::new(
+        ugli,
+        geng.ui_theme(),
+        geng.inner.options.target_ui_resolution,
+    );
+    let fixed_delta_time = geng.inner.fixed_delta_time.get();
+    let mut next_fixed_update = fixed_delta_time;
+    for _ in 0..options.frames {
+        state.update(options.delta_time);
+        ui_controller.update(&mut state.ui(&ui_controller), options.delta_time);
+        next_fixed_update -= options.delta_time;
+        while next_fixed_update <= 0.0 {
+            next_fixed_update += fixed_delta_time;
+            state.fixed_update(fixed_delta_time);
+        }
+        geng.inner
+            .fixed_update_alpha
+            .set(1.0 - next_fixed_update / fixed_delta_time);
+        let mut framebuffer = ugli::Framebuffer::new(
+            ugli,
+            ugli::ColorAttachment::Texture(&mut texture),
+            ugli::DepthAttachment::RenderbufferWithStencil(&mut depth),
+        );
+        state.draw(&mut framebuffer);
+        ui_controller.draw(&mut state.ui(&ui_controller), &mut framebuffer);
+    }
+    let framebuffer =
+        ugli::FramebufferRead::new_color(ugli, ugli::ColorAttachmentRead::Texture(&texture));
+    framebuffer.read_color().into_image_image()
+}
+
+/// Compare the image with the one stored at `path`.
+///
+/// Pixels are considered equal if every channel differs by at most `tolerance`.
+/// On mismatch, the actual image and a diff image highlighting differing pixels
+/// are written next to the golden one as `<name>.actual.png` and `<name>.diff.png`.
+///
+/// If [UPDATE_GOLDEN_ENV] is set, the image is saved as the new golden one instead.
+/// A missing golden image is an error otherwise, so that a misnamed one does not pass silently.
+pub fn check_golden(
+    actual: &image::RgbaImage,
+    path: impl AsRef<std::path::Path>,
+    tolerance: u8,
+) -> anyhow::Result<()> {
+    let update = std::env::var_os(UPDATE_GOLDEN_ENV).is_some();
+    compare_golden(actual, path.as_ref(), tolerance, update)
+}
+
+fn compare_golden(
+    actual: &image::RgbaImage,
+    path: &std::path::Path,
+    tolerance: u8,
+    update: bool,
+) -> anyhow::Result<()> {
+    if update {
+        log::info!("Saving golden image {path:?}");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(path)?;
+        return Ok(());
+    }
+    let sibling = |suffix: &str| {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!("{stem}.{suffix}.png"))
+    };
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(sibling("actual"))?;
+        anyhow::bail!("Golden image {path:?} does not exist, set {UPDATE_GOLDEN_ENV} to create it");
+    }
+    let expected = match image::open(path) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => anyhow::bail!("Failed to load {path:?}: {e}"),
+    };
+    if expected.dimensions() != actual.dimensions() {
+        actual.save(sibling("actual"))?;
+        anyhow::bail!(
+            "Image size {:?} does not match golden image {path:?} size {:?}",
+            actual.dimensions(),
+            expected.dimensions(),
+        );
+    }
+    let mut diff = image::RgbaImage::new(actual.width(), actual.height());
+    let mut mismatched = 0;
+    for ((actual, expected), diff) in actual
+        .pixels()
+        .zip(expected.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let differs = actual
+            .0
+            .iter()
+            .zip(expected.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > tolerance);
+        *diff = if differs {
+            mismatched += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            // Dimmed expected image so that mismatches stand out
+            image::Rgba([expected[0] / 4, expected[1] / 4, expected[2] / 4, 255])
+        };
+    }
+    if mismatched != 0 {
+        actual.save(sibling("actual"))?;
+        diff.save(sibling("diff"))?;
+        anyhow::bail!(
+            "{mismatched} pixels differ from golden image {path:?} (tolerance {tolerance})",
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("geng-golden-{}", std::process::id()))
+            .join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn image(color: [u8; 4]) -> image::RgbaImage {
+        image::RgbaImage::from_pixel(4, 4, image::Rgba(color))
+    }
+
+    #[test]
+    fn matching_image_passes() {
+        let path = test_dir("match").join("golden.png");
+        image([10, 20, 30, 255]).save(&path).unwrap();
+        compare_golden(&image([12, 20, 30, 255]), &path, 2, false).unwrap();
+    }
+
+    #[test]
+    fn mismatching_image_fails() {
+        let path = test_dir("mismatch").join("golden.png");
+        image([10, 20, 30, 255]).save(&path).unwrap();
+        assert!(compare_golden(&image([13, 20, 30, 255]), &path, 2, false).is_err());
+        assert!(path.with_file_name("golden.actual.png").exists());
+        assert!(path.with_file_name("golden.diff.png").exists());
+    }
+
+    #[test]
+    fn missing_golden_fails_unless_updating() {
+        let path = test_dir("missing").join("golden.png");
+        assert!(compare_golden(&image([0, 0, 0, 255]), &path, 0, false).is_err());
+        assert!(!path.exists());
+        compare_golden(&image([0, 0, 0, 255]), &path, 0, true).unwrap();
+        compare_golden(&image([0, 0, 0, 255]), &path, 0, false).unwrap();
+    }
+}